@@ -6,15 +6,24 @@ import "@openzeppelin/contracts-upgradeable/proxy/utils/UUPSUpgradeable.sol";
 import "@openzeppelin/contracts-upgradeable/security/PausableUpgradeable.sol";
 import "@openzeppelin/contracts/token/ERC20/IERC20.sol";
 import "@openzeppelin/contracts/token/ERC20/utils/SafeERC20.sol";
+import "./IFastBridgeRecipient.sol";
 
 contract EthErc20FastBridge is Initializable, UUPSUpgradeable, AccessControlUpgradeable, PausableUpgradeable {
     using SafeERC20 for IERC20;
     bytes32 public constant PAUSABLE_ADMIN_ROLE = keccak256("PAUSABLE_ADMIN_ROLE");
     bytes32 public constant UNPAUSABLE_ADMIN_ROLE = keccak256("UNPAUSABLE_ADMIN_ROLE");
     bytes32 public constant WHITELISTING_TOKENS_ADMIN_ROLE = keccak256("WHITELISTING_TOKENS_ADMIN_ROLE");
+    // The maximal number of the partial fills of a transfer, it bounds the claimed fills stored on the NEAR side
+    uint256 public constant MAX_PARTIAL_FILLS = 10;
 
     mapping(address => bool) public whitelistedTokens;
     mapping(bytes32 => bool) public processedHashes;
+    mapping(bytes32 => uint256) public filledAmounts;
+    // The reentrancy lock of the transfers, it's appended to the storage to keep the layout of the upgradeable contract
+    uint256 private transferLockStatus;
+
+    uint256 private constant TRANSFER_NOT_ENTERED = 0;
+    uint256 private constant TRANSFER_ENTERED = 1;
 
     event SetTokens(address[] _tokens, bool[] _states);
 
@@ -28,6 +37,28 @@ contract EthErc20FastBridge is Initializable, UUPSUpgradeable, AccessControlUpgr
         bytes32 indexed _transfer_id
     );
 
+    event TransferTokensPartially(
+        uint256 indexed _nonce,
+        address _relayer,
+        address _token,
+        address _recipient,
+        uint256 _fill_amount,
+        uint256 _filled_amount,
+        string _unlock_recipient,
+        bytes32 indexed _transfer_id
+    );
+
+    event TransferTokensWithPayload(
+        uint256 indexed _nonce,
+        address _relayer,
+        address _token,
+        address _recipient,
+        uint256 _amount,
+        string _unlock_recipient,
+        bytes32 _payload_hash,
+        bytes32 indexed _transfer_id
+    );
+
     event AddTokenToWhitelist(address token);
 
     event RemoveTokenFromWhitelist(address token);
@@ -37,6 +68,13 @@ contract EthErc20FastBridge is Initializable, UUPSUpgradeable, AccessControlUpgr
         _;
     }
 
+    modifier nonReentrant() {
+        require(transferLockStatus == TRANSFER_NOT_ENTERED, "Reentrant call");
+        transferLockStatus = TRANSFER_ENTERED;
+        _;
+        transferLockStatus = TRANSFER_NOT_ENTERED;
+    }
+
     function initialize(address[] memory _tokens, bool[] memory _states) public initializer {
         __Pausable_init();
         __AccessControl_init();
@@ -131,7 +169,7 @@ contract EthErc20FastBridge is Initializable, UUPSUpgradeable, AccessControlUpgr
       * Requirements:
       * - Contract must not be paused.
       * - Token must be whitelisted.
-      * - The transaction must not have already been processed.
+      * - The transaction must not have already been processed or partially filled.
       * - The recipient address must not be 0 and must not be the same as the sender.
       * - The amount must not be 0.
       * - The transaction must not have expired.
@@ -146,7 +184,7 @@ contract EthErc20FastBridge is Initializable, UUPSUpgradeable, AccessControlUpgr
         uint256 _amount,
         string calldata _unlock_recipient,
         uint256 _valid_till_block_height
-    ) external payable whenNotPaused isWhitelisted(_token) {
+    ) external payable whenNotPaused nonReentrant isWhitelisted(_token) {
         require(block.number < _valid_till_block_height, "Transfer expired");
         require(_recipient != address(0) && _recipient != msg.sender, "Wrong recipient provided");
         require(_amount != 0, "Wrong amount provided");
@@ -154,20 +192,140 @@ contract EthErc20FastBridge is Initializable, UUPSUpgradeable, AccessControlUpgr
         bytes32 processedHash = keccak256(abi.encodePacked(_token, _recipient, _nonce, _amount));
 
         require(!processedHashes[processedHash], "This transaction has already been processed!");
+        require(filledAmounts[processedHash] == 0, "This transaction has already been partially filled!");
         processedHashes[processedHash] = true;
+        filledAmounts[processedHash] = _amount;
 
-        if (_token == address(0)) {
-            require(_amount == msg.value, "Wrong ethers amount provided");
-            _recipient.transfer(_amount);
-        } else {
-            require(msg.value == 0, "Ethers not accepted for ERC-20 transfers");
-            IERC20 token = IERC20(_token);
-            token.safeTransferFrom(msg.sender, _recipient, _amount);
-        }
+        _transfer(_token, _recipient, _amount);
         // slither-disable-next-line reentrancy-events
         emit TransferTokens(_nonce, msg.sender, _token, _recipient, _amount, _unlock_recipient, processedHash);
     }
 
+    /**
+      * @dev Transfers a part of the NEAR transfer to the recipient, so a large transfer may be filled by several relayers. Should be used only for the transfers that allow partial fills on the NEAR side.
+      * @param _token The address of the token to be transferred. Use `address(0)` for Ether transfers.
+      * @param _recipient The address of the recipient to receive the tokens or Ether.
+      * @param _nonce A unique number, an identifier of the NEAR transfer.
+      * @param _amount The total amount of the NEAR transfer, it's a part of the transfer identifier.
+      * @param _fill_amount The amount of tokens or Ether transferred by this fill.
+      * @param _unlock_recipient The NEAR account that will be able to claim the filled part of the transfer on the NEAR side.
+      * @param _valid_till_block_height The block height until which the transaction can be processed.
+      * Requirements:
+      * - Contract must not be paused.
+      * - Token must be whitelisted.
+      * - The transaction must not have already been processed.
+      * - The recipient address must not be 0 and must not be the same as the sender.
+      * - The fill amount must not be 0 and the total filled amount must not exceed the transfer amount.
+      * - The fill amount must be at least `1 / MAX_PARTIAL_FILLS` of the transfer amount, unless it completes the transfer.
+      * - The transaction must not have expired.
+      * Effects:
+      * - Increases the filled amount of the transfer, the transfer is marked as processed once it's filled completely.
+      * - Transfers the fill amount of tokens or Ether to the recipient.
+      * - Emits a `TransferTokensPartially` event with the fill amount and the total filled amount. The `unlock_recipient` can use proof for this event to claim the pro-rata part of the transfer on the NEAR side.
+    */
+    function transferTokensPartially(
+        address _token,
+        address payable _recipient,
+        uint256 _nonce,
+        uint256 _amount,
+        uint256 _fill_amount,
+        string calldata _unlock_recipient,
+        uint256 _valid_till_block_height
+    ) external payable whenNotPaused nonReentrant isWhitelisted(_token) {
+        require(block.number < _valid_till_block_height, "Transfer expired");
+        require(_recipient != address(0) && _recipient != msg.sender, "Wrong recipient provided");
+        require(_fill_amount != 0, "Wrong amount provided");
+
+        bytes32 processedHash = keccak256(abi.encodePacked(_token, _recipient, _nonce, _amount));
+
+        require(!processedHashes[processedHash], "This transaction has already been processed!");
+        uint256 filledAmount = filledAmounts[processedHash] + _fill_amount;
+        require(filledAmount <= _amount, "The fill amount exceeds the unfilled amount");
+        uint256 minFillAmount = _amount / MAX_PARTIAL_FILLS + (_amount % MAX_PARTIAL_FILLS == 0 ? 0 : 1);
+        require(
+            _fill_amount >= minFillAmount || filledAmount == _amount,
+            "The fill amount is below the minimal fill amount"
+        );
+        filledAmounts[processedHash] = filledAmount;
+        if (filledAmount == _amount) {
+            processedHashes[processedHash] = true;
+        }
+
+        _transfer(_token, _recipient, _fill_amount);
+        // slither-disable-next-line reentrancy-events
+        emit TransferTokensPartially(
+            _nonce,
+            msg.sender,
+            _token,
+            _recipient,
+            _fill_amount,
+            filledAmount,
+            _unlock_recipient,
+            processedHash
+        );
+    }
+
+    /**
+      * @dev Transfers tokens or Ether to the recipient contract and calls its `onFastBridgeTransfer` with the payload, so the recipient can perform an action on arrival. Should be used only for the NEAR transfers that are bound to the payload hash.
+      * @param _token The address of the token to be transferred. Use `address(0)` for Ether transfers.
+      * @param _recipient The address of the recipient contract that implements `IFastBridgeRecipient`.
+      * @param _nonce A unique number, an identifier of the NEAR transfer.
+      * @param _amount The amount of tokens or Ether to be transferred.
+      * @param _payload The payload passed to `onFastBridgeTransfer`, its hash is a part of the transfer identifier.
+      * @param _unlock_recipient The NEAR account that will be able to claim the transfer on the NEAR side.
+      * @param _valid_till_block_height The block height until which the transaction can be processed.
+      * Requirements:
+      * - Contract must not be paused.
+      * - Token must be whitelisted.
+      * - The transaction must not have already been processed.
+      * - The recipient address must not be 0 and must not be the same as the sender.
+      * - The amount must not be 0.
+      * - The transaction must not have expired.
+      * - The recipient must accept the payload by returning the selector of `onFastBridgeTransfer`.
+      * Effects:
+      * - Transfers the specified amount of tokens or Ether to the recipient and calls its `onFastBridgeTransfer` with the payload.
+      * - Emits a `TransferTokensWithPayload` event with the payload hash. The `unlock_recipient` can use proof for this event to claim tokens on the NEAR side.
+    */
+    function transferTokensWithPayload(
+        address _token,
+        address payable _recipient,
+        uint256 _nonce,
+        uint256 _amount,
+        bytes calldata _payload,
+        string calldata _unlock_recipient,
+        uint256 _valid_till_block_height
+    ) external payable whenNotPaused nonReentrant isWhitelisted(_token) {
+        require(block.number < _valid_till_block_height, "Transfer expired");
+        require(_recipient != address(0) && _recipient != msg.sender, "Wrong recipient provided");
+        require(_amount != 0, "Wrong amount provided");
+
+        bytes32 payloadHash = keccak256(_payload);
+        bytes32 processedHash = keccak256(abi.encodePacked(_token, _recipient, _nonce, _amount, payloadHash));
+
+        require(!processedHashes[processedHash], "This transaction has already been processed!");
+        processedHashes[processedHash] = true;
+
+        _transfer(_token, _recipient, _amount);
+        // The recipient is called only through the fixed callback, so the payload can't make the bridge call
+        // the tokens that the relayers approved to it
+        require(
+            IFastBridgeRecipient(_recipient).onFastBridgeTransfer(_token, _amount, _payload) ==
+                IFastBridgeRecipient.onFastBridgeTransfer.selector,
+            "The payload isn't accepted by the recipient"
+        );
+        // slither-disable-next-line reentrancy-events
+        emit TransferTokensWithPayload(
+            _nonce,
+            msg.sender,
+            _token,
+            _recipient,
+            _amount,
+            _unlock_recipient,
+            payloadHash,
+            processedHash
+        );
+    }
+
     /**
       * @dev Allows the contract owner to withdraw tokens accidentally transferred to this contract.
       * @param _token The address of the token to be withdrawn.
@@ -179,6 +337,17 @@ contract EthErc20FastBridge is Initializable, UUPSUpgradeable, AccessControlUpgr
         token.safeTransfer(msg.sender, token.balanceOf(address(this)));
     }
 
+    function _transfer(address _token, address payable _recipient, uint256 _amount) internal {
+        if (_token == address(0)) {
+            require(_amount == msg.value, "Wrong ethers amount provided");
+            _recipient.transfer(_amount);
+        } else {
+            require(msg.value == 0, "Ethers not accepted for ERC-20 transfers");
+            IERC20 token = IERC20(_token);
+            token.safeTransferFrom(msg.sender, _recipient, _amount);
+        }
+    }
+
     /**
       * @dev Internal function called by the proxy contract to authorize an upgrade to a new implementation address
       * using the UUPS proxy upgrade pattern. Overrides the default `_authorizeUpgrade` function from the `UUPSUpgradeable` contract.
@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: MIT
+pragma solidity 0.8.11;
+
+/**
+  * @dev The interface of the recipient contracts of the transfers with the payload. The bridge transfers the tokens
+  * or Ether to the recipient and then calls `onFastBridgeTransfer` with the payload, the bridge never makes any other
+  * calls to the recipient.
+*/
+interface IFastBridgeRecipient {
+    /**
+      * @dev Performs the action of the payload on arrival of the transfer.
+      * @param _token The address of the transferred token, `address(0)` for Ether transfers.
+      * @param _amount The transferred amount of tokens or Ether.
+      * @param _payload The payload of the transfer.
+      * @return The selector of this function, any other value reverts the transfer.
+    */
+    function onFastBridgeTransfer(
+        address _token,
+        uint256 _amount,
+        bytes calldata _payload
+    ) external returns (bytes4);
+}
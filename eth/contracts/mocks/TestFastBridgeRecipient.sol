@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: MIT
+pragma solidity 0.8.11;
+import "../IFastBridgeRecipient.sol";
+
+contract TestFastBridgeRecipient is IFastBridgeRecipient {
+    event FastBridgeTransferReceived(address _token, uint256 _amount, bytes _payload);
+
+    receive() external payable {}
+
+    function onFastBridgeTransfer(
+        address _token,
+        uint256 _amount,
+        bytes calldata _payload
+    ) external override returns (bytes4) {
+        emit FastBridgeTransferReceived(_token, _amount, _payload);
+        return IFastBridgeRecipient.onFastBridgeTransfer.selector;
+    }
+}
@@ -20,6 +20,13 @@ function getTransferId(token, recipient, nonce, amount) {
     );
 }
 
+function getTransferIdWithPayload(token, recipient, nonce, amount, payloadHash) {
+    return ethers.utils.solidityKeccak256(
+        ["address", "address", "uint256", "uint256", "bytes32"],
+        [token, recipient, nonce, amount, payloadHash]
+    );
+}
+
 const buyTokenForEth = async (buyer, router, ethAmount, path) => {
     await router
         .connect(buyer)
@@ -413,6 +420,242 @@ describe("Fast Bridge", () => {
             ).to.be.revertedWith("Wrong amount provided");
         });
 
+        it("Should fill the transfer partially by two relayers", async () => {
+            await expect(proxy.connect(whitelistingAdmin).setWhitelistedTokens([tokenAddress], [true]))
+                .to.emit(proxy, "SetTokens")
+                .withArgs([tokenAddress], [true]);
+
+            await tokenInstance.connect(relayer).approve(proxy.address, await tokenInstance.balanceOf(relayer.address));
+            await tokenInstance
+                .connect(anotherRelayer)
+                .approve(proxy.address, await tokenInstance.balanceOf(anotherRelayer.address));
+
+            const amount = 100;
+            const transferId = getTransferId(tokenAddress, someone.address, nonce, amount);
+            await expect(
+                proxy
+                    .connect(relayer)
+                    .transferTokensPartially(
+                        tokenAddress,
+                        someone.address,
+                        nonce,
+                        amount,
+                        30,
+                        unlockRecipient,
+                        defaultValidTillBlockHeight
+                    )
+            )
+                .to.emit(proxy, "TransferTokensPartially")
+                .withArgs(nonce, relayer.address, tokenAddress, someone.address, 30, 30, unlockRecipient, transferId);
+
+            expect(await proxy.filledAmounts(transferId)).to.be.equal(30);
+            expect(await proxy.processedHashes(transferId)).to.be.false;
+
+            await expect(
+                proxy
+                    .connect(anotherRelayer)
+                    .transferTokens(
+                        tokenAddress,
+                        someone.address,
+                        nonce,
+                        amount,
+                        unlockRecipient,
+                        defaultValidTillBlockHeight
+                    )
+            ).to.be.revertedWith("This transaction has already been partially filled!");
+
+            await expect(
+                proxy
+                    .connect(anotherRelayer)
+                    .transferTokensPartially(
+                        tokenAddress,
+                        someone.address,
+                        nonce,
+                        amount,
+                        71,
+                        unlockRecipient,
+                        defaultValidTillBlockHeight
+                    )
+            ).to.be.revertedWith("The fill amount exceeds the unfilled amount");
+
+            await expect(
+                proxy
+                    .connect(anotherRelayer)
+                    .transferTokensPartially(
+                        tokenAddress,
+                        someone.address,
+                        nonce,
+                        amount,
+                        70,
+                        unlockRecipient,
+                        defaultValidTillBlockHeight
+                    )
+            )
+                .to.emit(proxy, "TransferTokensPartially")
+                .withArgs(
+                    nonce,
+                    anotherRelayer.address,
+                    tokenAddress,
+                    someone.address,
+                    70,
+                    amount,
+                    unlockRecipient,
+                    transferId
+                );
+
+            expect(await tokenInstance.balanceOf(someone.address)).to.be.equal(amount);
+            expect(await proxy.processedHashes(transferId)).to.be.true;
+        });
+
+        it("Should reject the partial fill below the minimal fill amount", async () => {
+            await expect(proxy.connect(whitelistingAdmin).setWhitelistedTokens([tokenAddress], [true]))
+                .to.emit(proxy, "SetTokens")
+                .withArgs([tokenAddress], [true]);
+
+            await tokenInstance.connect(relayer).approve(proxy.address, await tokenInstance.balanceOf(relayer.address));
+
+            const amount = 100;
+            const transferId = getTransferId(tokenAddress, someone.address, nonce, amount);
+            await expect(
+                proxy
+                    .connect(relayer)
+                    .transferTokensPartially(
+                        tokenAddress,
+                        someone.address,
+                        nonce,
+                        amount,
+                        9,
+                        unlockRecipient,
+                        defaultValidTillBlockHeight
+                    )
+            ).to.be.revertedWith("The fill amount is below the minimal fill amount");
+
+            await proxy
+                .connect(relayer)
+                .transferTokensPartially(
+                    tokenAddress,
+                    someone.address,
+                    nonce,
+                    amount,
+                    95,
+                    unlockRecipient,
+                    defaultValidTillBlockHeight
+                );
+
+            // The fill that completes the transfer may be below the minimal fill amount
+            await expect(
+                proxy
+                    .connect(relayer)
+                    .transferTokensPartially(
+                        tokenAddress,
+                        someone.address,
+                        nonce,
+                        amount,
+                        5,
+                        unlockRecipient,
+                        defaultValidTillBlockHeight
+                    )
+            )
+                .to.emit(proxy, "TransferTokensPartially")
+                .withArgs(nonce, relayer.address, tokenAddress, someone.address, 5, amount, unlockRecipient, transferId);
+            expect(await proxy.processedHashes(transferId)).to.be.true;
+        });
+
+        it("Should transfer token with the payload", async () => {
+            await expect(proxy.connect(whitelistingAdmin).setWhitelistedTokens([tokenAddress], [true]))
+                .to.emit(proxy, "SetTokens")
+                .withArgs([tokenAddress], [true]);
+
+            await tokenInstance.connect(relayer).approve(proxy.address, await tokenInstance.balanceOf(relayer.address));
+
+            const recipientFactory = await ethers.getContractFactory("TestFastBridgeRecipient");
+            const recipient = await recipientFactory.deploy();
+            await recipient.deployed();
+
+            const amount = 100;
+            const payload = "0x12345678";
+            const payloadHash = ethers.utils.keccak256(payload);
+            const transferId = getTransferIdWithPayload(tokenAddress, recipient.address, nonce, amount, payloadHash);
+            await expect(
+                proxy
+                    .connect(relayer)
+                    .transferTokensWithPayload(
+                        tokenAddress,
+                        recipient.address,
+                        nonce,
+                        amount,
+                        payload,
+                        unlockRecipient,
+                        defaultValidTillBlockHeight
+                    )
+            )
+                .to.emit(proxy, "TransferTokensWithPayload")
+                .withArgs(
+                    nonce,
+                    relayer.address,
+                    tokenAddress,
+                    recipient.address,
+                    amount,
+                    unlockRecipient,
+                    payloadHash,
+                    transferId
+                )
+                .to.emit(recipient, "FastBridgeTransferReceived")
+                .withArgs(tokenAddress, amount, payload);
+
+            expect(await tokenInstance.balanceOf(recipient.address)).to.be.equal(amount);
+            expect(await proxy.processedHashes(transferId)).to.be.true;
+            expect(await proxy.processedHashes(getTransferId(tokenAddress, recipient.address, nonce, amount))).to.be
+                .false;
+
+            await expect(
+                proxy
+                    .connect(relayer)
+                    .transferTokensWithPayload(
+                        tokenAddress,
+                        recipient.address,
+                        nonce,
+                        amount,
+                        payload,
+                        unlockRecipient,
+                        defaultValidTillBlockHeight
+                    )
+            ).to.be.revertedWith("This transaction has already been processed!");
+        });
+
+        it("Shouldn't let the payload spend the allowances of the relayers", async () => {
+            await expect(proxy.connect(whitelistingAdmin).setWhitelistedTokens([tokenAddress], [true]))
+                .to.emit(proxy, "SetTokens")
+                .withArgs([tokenAddress], [true]);
+
+            const allowance = await tokenInstance.balanceOf(relayer.address);
+            await tokenInstance.connect(relayer).approve(proxy.address, allowance);
+            await tokenInstance.connect(anotherRelayer).approve(proxy.address, 1);
+
+            // The token itself is the recipient and the payload tries to move the approved tokens of the relayer
+            const payload = tokenInstance.interface.encodeFunctionData("transferFrom", [
+                relayer.address,
+                someone.address,
+                allowance
+            ]);
+            await expect(
+                proxy
+                    .connect(anotherRelayer)
+                    .transferTokensWithPayload(
+                        tokenAddress,
+                        tokenAddress,
+                        nonce,
+                        1,
+                        payload,
+                        unlockRecipient,
+                        defaultValidTillBlockHeight
+                    )
+            ).to.be.reverted;
+
+            expect(await tokenInstance.allowance(relayer.address, proxy.address)).to.be.equal(allowance);
+            expect(await tokenInstance.balanceOf(relayer.address)).to.be.equal(allowance);
+        });
+
         it("Should transfer with ETH", async () => {
             await expect(proxy.connect(whitelistingAdmin).setWhitelistedTokens([relayerEthAddress], [true]))
                 .to.emit(proxy, "SetTokens")
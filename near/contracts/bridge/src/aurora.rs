@@ -0,0 +1,93 @@
+use near_plugins::access_control_any;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId};
+
+use crate::lp_relayer::UnlockRecipient;
+use crate::*;
+
+/// The Aurora deployment that receives the LP claims to Aurora addresses.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuroraConfig {
+    /// The account of the Aurora engine
+    pub engine_account: AccountId,
+    /// The account of the token that is the native currency of the Aurora EVM
+    pub native_token_account: AccountId,
+}
+
+/// The message of `ft_transfer_call` to the Aurora engine that deposits the `token` to the `aurora_address`.
+pub fn get_aurora_deposit_msg(
+    token: &AccountId,
+    native_token: &AccountId,
+    aurora_address: &EthAddress,
+) -> String {
+    let aurora_address_hex = hex::encode(aurora_address.0);
+    if native_token == token {
+        format!(
+            "fake.near:0000000000000000000000000000000000000000000000000000000000000000{}",
+            aurora_address_hex
+        )
+    } else {
+        aurora_address_hex
+    }
+}
+
+#[near_bindgen]
+impl FastBridge {
+    /// Sets the Aurora deployment for the LP claims to Aurora addresses, `None` disables such claims.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn set_aurora_config(&mut self, config: Option<AuroraConfig>) {
+        let previous_config = std::mem::replace(&mut self.aurora_config, config.clone());
+
+        FastBridgeEvent::FastBridgeSetAuroraConfigEvent {
+            previous_config,
+            config,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn get_aurora_config(&self) -> Option<AuroraConfig> {
+        self.aurora_config.clone()
+    }
+}
+
+impl FastBridge {
+    /// Withdraws the tokens of the LP claim to the Aurora address, if the `unlock_recipient` is one. The tokens
+    /// are credited to the Ethereum-implicit `account_id` of the address beforehand, so a failed deposit returns
+    /// them to the balance of that account.
+    pub(crate) fn withdraw_lp_unlock_to_aurora(
+        &mut self,
+        unlock_recipient: &UnlockRecipient,
+        account_id: &AccountId,
+        transfer_message: &TransferMessage,
+    ) {
+        let UnlockRecipient::Aurora(aurora_address) = unlock_recipient else {
+            return;
+        };
+        let config = self
+            .aurora_config
+            .clone()
+            .unwrap_or_else(|| env::panic_str("The claims to Aurora are disabled"));
+
+        for token in [
+            &transfer_message.transfer.token_near,
+            &transfer_message.fee.token,
+        ] {
+            if self.get_user_balance(account_id, token).0 == 0 {
+                continue;
+            }
+
+            let msg = get_aurora_deposit_msg(token, &config.native_token_account, aurora_address);
+            self.withdraw_internal(
+                token.clone(),
+                None,
+                account_id.clone(),
+                Some(config.engine_account.clone()),
+                Some(msg),
+            );
+        }
+    }
+}
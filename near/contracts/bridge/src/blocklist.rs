@@ -0,0 +1,154 @@
+use near_plugins::access_control_any;
+use near_sdk::{env, AccountId};
+
+use crate::*;
+
+#[near_bindgen]
+impl FastBridge {
+    /// Adds the `account` to the blocklist. Blocked accounts can't deposit, initialize transfers,
+    /// withdraw or receive unlocked tokens regardless of the whitelist mode.
+    ///
+    /// The function is allowed to be called only by accounts that have `BlocklistManager` or `DAO` roles.
+    #[access_control_any(roles(Role::BlocklistManager, Role::DAO))]
+    pub fn add_account_to_blocklist(&mut self, account: AccountId) -> bool {
+        let inserted = self.blocked_accounts.insert(&account);
+
+        FastBridgeEvent::FastBridgeAddAccountToBlocklistEvent {
+            account,
+            was_blocked: !inserted,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+
+        inserted
+    }
+
+    /// Removes the `account` from the blocklist.
+    ///
+    /// The function is allowed to be called only by accounts that have `BlocklistManager` or `DAO` roles.
+    #[access_control_any(roles(Role::BlocklistManager, Role::DAO))]
+    pub fn remove_account_from_blocklist(&mut self, account: AccountId) -> bool {
+        let removed = self.blocked_accounts.remove(&account);
+
+        FastBridgeEvent::FastBridgeRemoveAccountFromBlocklistEvent {
+            account,
+            was_blocked: removed,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+
+        removed
+    }
+
+    pub fn is_account_blocked(&self, account: AccountId) -> bool {
+        self.blocked_accounts.contains(&account)
+    }
+
+    /// Returns a page of the blocked accounts, starting at the specified `from_index`
+    /// and containing a maximum of `limit` accounts.
+    pub fn get_blocked_accounts(&self, from_index: usize, limit: usize) -> Vec<AccountId> {
+        self.blocked_accounts
+            .iter()
+            .skip(from_index)
+            .take(limit)
+            .collect::<Vec<_>>()
+    }
+
+    /// Adds the Ethereum `addresses` to the denylist. A transfer can't be initialized if its recipient
+    /// or Ethereum token address is denied.
+    ///
+    /// The function is allowed to be called only by accounts that have `WhitelistManager` or `DAO` roles.
+    ///
+    /// # Arguments
+    ///
+    /// * `addresses` - hex-encoded Ethereum addresses.
+    ///
+    /// # Returns
+    ///
+    /// For each address, whether it was added to the denylist or was already denied.
+    #[access_control_any(roles(Role::WhitelistManager, Role::DAO))]
+    pub fn add_eth_addresses_to_denylist(&mut self, addresses: Vec<String>) -> Vec<bool> {
+        addresses
+            .into_iter()
+            .map(|address| {
+                let address = get_eth_address(address);
+                let inserted = self.eth_address_denylist.insert(&address);
+
+                FastBridgeEvent::FastBridgeAddEthAddressToDenylistEvent {
+                    address,
+                    was_denied: !inserted,
+                    changed_by: env::predecessor_account_id(),
+                }
+                .emit();
+
+                inserted
+            })
+            .collect()
+    }
+
+    /// Removes the Ethereum `addresses` from the denylist.
+    ///
+    /// The function is allowed to be called only by accounts that have `WhitelistManager` or `DAO` roles.
+    ///
+    /// # Arguments
+    ///
+    /// * `addresses` - hex-encoded Ethereum addresses.
+    ///
+    /// # Returns
+    ///
+    /// For each address, whether it was removed from the denylist or wasn't denied.
+    #[access_control_any(roles(Role::WhitelistManager, Role::DAO))]
+    pub fn remove_eth_addresses_from_denylist(&mut self, addresses: Vec<String>) -> Vec<bool> {
+        addresses
+            .into_iter()
+            .map(|address| {
+                let address = get_eth_address(address);
+                let removed = self.eth_address_denylist.remove(&address);
+
+                FastBridgeEvent::FastBridgeRemoveEthAddressFromDenylistEvent {
+                    address,
+                    was_denied: removed,
+                    changed_by: env::predecessor_account_id(),
+                }
+                .emit();
+
+                removed
+            })
+            .collect()
+    }
+
+    /// Checks whether the hex-encoded Ethereum `address` is denied.
+    pub fn is_eth_address_denied(&self, address: String) -> bool {
+        self.eth_address_denylist
+            .contains(&get_eth_address(address))
+    }
+
+    /// Returns a page of the denied Ethereum addresses, starting at the specified `from_index`
+    /// and containing a maximum of `limit` addresses.
+    pub fn get_eth_address_denylist(&self, from_index: usize, limit: usize) -> Vec<EthAddress> {
+        self.eth_address_denylist
+            .iter()
+            .skip(from_index)
+            .take(limit)
+            .collect::<Vec<_>>()
+    }
+}
+
+impl FastBridge {
+    pub(crate) fn check_account_not_blocked(&self, account: &AccountId) {
+        require!(
+            !self.blocked_accounts.contains(account),
+            format!("The account `{}` is blocked", account)
+        );
+    }
+
+    pub(crate) fn check_eth_address_not_denied(&self, address: &EthAddress) {
+        require!(
+            !self.eth_address_denylist.contains(address),
+            format!(
+                "The Ethereum address `{}` is denied",
+                hex::encode(address.0)
+            )
+        );
+    }
+}
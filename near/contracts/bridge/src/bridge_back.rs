@@ -0,0 +1,115 @@
+use near_sdk::json_types::U128;
+use near_sdk::{env, ext_contract, AccountId, Promise};
+
+use crate::*;
+
+/// The token deployed by the Rainbow Bridge token factory for an ERC-20 token.
+#[ext_contract(ext_bridge_token)]
+trait RainbowBridgeToken {
+    fn withdraw(&mut self, amount: U128, recipient: String) -> Promise;
+}
+
+#[near_bindgen]
+impl FastBridge {
+    /// Unlocks tokens that were transferred on the Ethereum like `lp_unlock()` and then bridges the amount
+    /// and the fee credited to the caller back to the `eth_address` with the Rainbow Bridge `withdraw`
+    /// of the tokens. If the withdrawal fails, the tokens are returned to the balance of the caller
+    /// in this contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `proof` - A `Proof` for the event of the successful transfer on the Ethereum side.
+    /// * `eth_address` - The hex-encoded Ethereum address that receives the tokens.
+    ///
+    /// # Panics
+    ///
+    /// The function will panic if the caller isn't the `unlock_recipient` of the event or the `eth_address`
+    /// is denied.
+    #[pause(except(roles(Role::UnrestrictedLpUnlock)))]
+    pub fn lp_unlock_and_bridge_back(&mut self, proof: Proof, eth_address: String) -> Promise {
+        let parsed_proof = lp_relayer::EthTransferEvent::parse(proof.clone());
+        let sender_id = get_caller_as_unlock_recipient(&parsed_proof);
+        let eth_address = get_eth_address(eth_address);
+        self.check_eth_address_not_denied(&eth_address);
+
+        self.lp_unlock_internal(proof, parsed_proof).then(
+            ext_self::ext(current_account_id())
+                .with_static_gas(utils::tera_gas(90))
+                .with_attached_deposit(utils::NO_DEPOSIT)
+                .lp_unlock_and_bridge_back_callback(sender_id, eth_address),
+        )
+    }
+
+    #[private]
+    pub fn lp_unlock_and_bridge_back_callback(
+        &mut self,
+        #[callback]
+        #[serializer(borsh)]
+        transfer_message: TransferMessage,
+        #[serializer(borsh)] sender_id: AccountId,
+        #[serializer(borsh)] eth_address: EthAddress,
+    ) -> Promise {
+        self.check_account_not_blocked(&sender_id);
+
+        get_unlocked_token_amounts(&transfer_message)
+            .into_iter()
+            .map(|(token, amount)| {
+                self.bridge_back_internal(token, amount, &sender_id, eth_address)
+            })
+            .reduce(|promise, withdraw_promise| promise.and(withdraw_promise))
+            .unwrap_or_else(|| env::panic_str("Nothing to withdraw"))
+    }
+
+    /// Finalizes the Rainbow Bridge withdrawal of the `lp_unlock_and_bridge_back()`. If the withdrawal failed,
+    /// the `amount` is returned to the balance of the `sender_id`.
+    #[private]
+    pub fn bridge_back_callback(
+        &mut self,
+        token_id: AccountId,
+        amount: U128,
+        sender_id: AccountId,
+        eth_address: EthAddress,
+    ) -> U128 {
+        if promise_result_as_success().is_none() {
+            self.increase_balance(&sender_id, &token_id, &amount.0);
+            return U128(0);
+        }
+
+        FastBridgeEvent::FastBridgeLpBridgeBackEvent {
+            sender_id,
+            token: token_id,
+            amount,
+            eth_address,
+        }
+        .emit();
+
+        amount
+    }
+}
+
+impl FastBridge {
+    fn bridge_back_internal(
+        &mut self,
+        token_id: AccountId,
+        amount: u128,
+        sender_id: &AccountId,
+        eth_address: EthAddress,
+    ) -> Promise {
+        require!(
+            self.get_user_balance(sender_id, &token_id).0 >= amount,
+            "Insufficient user balance"
+        );
+        self.decrease_balance(sender_id, &token_id, &amount);
+
+        ext_bridge_token::ext(token_id.clone())
+            .with_static_gas(utils::tera_gas(30))
+            .with_attached_deposit(1)
+            .withdraw(U128(amount), hex::encode(eth_address.0))
+            .then(
+                ext_self::ext(current_account_id())
+                    .with_static_gas(utils::tera_gas(5))
+                    .with_attached_deposit(utils::NO_DEPOSIT)
+                    .bridge_back_callback(token_id, U128(amount), sender_id.clone(), eth_address),
+            )
+    }
+}
@@ -0,0 +1,226 @@
+use ethabi::Token;
+use near_plugins::access_control_any;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId};
+
+use crate::proof_verifier::ProofVerifier;
+use crate::*;
+
+const EIP712_DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,address verifyingContract)";
+const EIP712_DOMAIN_NAME: &str = "Fast Bridge Committee";
+const EIP712_DOMAIN_VERSION: &str = "1";
+const TRANSFER_TOKENS_TYPE: &str = "TransferTokens(uint256 nonce,address relayer,address token,address recipient,uint256 amount,uint256 filledAmount,string unlockRecipient,bytes32 transferId)";
+
+/// The committee of Ethereum keys that attests the transfer events of the Ethereum Fast Bridge.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommitteeConfig {
+    pub members: Vec<EthAddress>,
+    /// The number of the members that should attest the event
+    pub threshold: u32,
+}
+
+/// The `TransferTokens`, `TransferTokensPartially` or `TransferTokensWithPayload` event of the Ethereum Fast Bridge
+/// attested by the committee.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AttestedTransferEvent {
+    pub nonce: U128,
+    /// The hex-encoded address of the relayer
+    pub relayer: String,
+    /// The hex-encoded address of the token
+    pub token: String,
+    /// The hex-encoded address of the recipient
+    pub recipient: String,
+    pub amount: U128,
+    /// The total filled amount of the partial fill, `None` for the `TransferTokens` event
+    pub filled_amount: Option<U128>,
+    pub unlock_recipient: String,
+    /// The hex-encoded id of the transfer
+    pub transfer_id: String,
+    /// The hex-encoded hash of the payload, `None` for the events without the payload. The hash is bound
+    /// to the attested transfer id, so it isn't a part of the attestation.
+    pub payload_hash: Option<String>,
+}
+
+impl AttestedTransferEvent {
+    fn into_eth_transfer_event(self, eth_bridge_contract: EthAddress) -> EthTransferEvent {
+        let transfer_id = hex::decode(self.transfer_id.trim_start_matches("0x"))
+            .ok()
+            .and_then(|transfer_id| transfer_id.try_into().ok())
+            .unwrap_or_else(|| env::panic_str("Invalid transfer id"));
+        let payload_hash = self.payload_hash.map(|payload_hash| {
+            hex::decode(payload_hash.trim_start_matches("0x"))
+                .ok()
+                .and_then(|payload_hash| payload_hash.try_into().ok())
+                .unwrap_or_else(|| env::panic_str("Invalid payload hash"))
+        });
+
+        EthTransferEvent {
+            eth_bridge_contract,
+            nonce: self.nonce.0,
+            relayer: get_eth_address(self.relayer),
+            token: get_eth_address(self.token),
+            recipient: get_eth_address(self.recipient),
+            amount: self.amount.0,
+            filled_amount: self.filled_amount.map(|filled_amount| filled_amount.0),
+            unlock_recipient: self.unlock_recipient,
+            transfer_id,
+            payload_hash,
+            eth_block_height: None,
+        }
+    }
+}
+
+/// The EIP-712 hash of the transfer event that the committee members sign. The `filledAmount` is zero
+/// for the `TransferTokens` event and the `verifyingContract` is the Ethereum Fast Bridge.
+pub fn get_attestation_hash(event: &EthTransferEvent) -> Vec<u8> {
+    let domain_separator = env::keccak256(&ethabi::encode(&[
+        Token::FixedBytes(env::keccak256(EIP712_DOMAIN_TYPE.as_bytes())),
+        Token::FixedBytes(env::keccak256(EIP712_DOMAIN_NAME.as_bytes())),
+        Token::FixedBytes(env::keccak256(EIP712_DOMAIN_VERSION.as_bytes())),
+        Token::Address(event.eth_bridge_contract.0.into()),
+    ]));
+    let struct_hash = env::keccak256(&ethabi::encode(&[
+        Token::FixedBytes(env::keccak256(TRANSFER_TOKENS_TYPE.as_bytes())),
+        Token::Uint(event.nonce.into()),
+        Token::Address(event.relayer.0.into()),
+        Token::Address(event.token.0.into()),
+        Token::Address(event.recipient.0.into()),
+        Token::Uint(event.amount.into()),
+        Token::Uint(event.filled_amount.unwrap_or(0).into()),
+        Token::FixedBytes(env::keccak256(event.unlock_recipient.as_bytes())),
+        Token::FixedBytes(event.transfer_id.0 .0.to_vec()),
+    ]));
+
+    eth_signature::typed_data_hash(&domain_separator, &struct_hash)
+}
+
+#[near_bindgen]
+impl FastBridge {
+    /// Unlocks tokens that were transferred on the Ethereum like `lp_unlock()`, but the transfer event is attested
+    /// by the signatures of the committee instead of the proof. The LP is credited immediately. The attestations
+    /// are accepted only for the tokens with the `Committee` proof verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The attested transfer event.
+    /// * `signatures` - The hex-encoded 65-byte EIP-712 signatures of the event by the committee members.
+    ///
+    /// # Panics
+    ///
+    /// The function will panic if less than the threshold of the committee members signed the event.
+    #[pause(except(roles(Role::UnrestrictedLpUnlock)))]
+    pub fn lp_unlock_with_attestations(
+        &mut self,
+        event: AttestedTransferEvent,
+        signatures: Vec<String>,
+    ) -> TransferMessage {
+        let event = event.into_eth_transfer_event(self.eth_bridge_contract);
+        let (_, transfer_message) = self
+            .pending_transfers
+            .get(&event.nonce.to_string())
+            .unwrap_or_else(|| env::panic_str("Transfer not found"));
+        require!(
+            self.get_token_proof_verifier(&transfer_message.transfer.token_near)
+                == ProofVerifier::Committee,
+            "The transfer events of the token aren't attested by the committee"
+        );
+        let committee = self
+            .committee
+            .clone()
+            .unwrap_or_else(|| env::panic_str("The committee isn't set"));
+        self.check_unlock_recipient(&event.unlock_recipient);
+
+        let hash = get_attestation_hash(&event);
+        let mut signers: Vec<EthAddress> = vec![];
+        for signature in signatures {
+            let signature = hex::decode(signature.trim_start_matches("0x"))
+                .unwrap_or_else(|_| env::panic_str("Invalid hex format of the signature"));
+            let Some(signer) = eth_signature::recover_eth_address(&hash, &signature) else {
+                env::panic_str("Invalid signature");
+            };
+            if committee.members.contains(&signer) && !signers.contains(&signer) {
+                signers.push(signer);
+            }
+        }
+        require!(
+            signers.len() >= committee.threshold as usize,
+            format!(
+                "Not enough committee attestations {}, expected {}",
+                signers.len(),
+                committee.threshold
+            )
+        );
+
+        self.lp_unlock_verified(event)
+    }
+
+    /// Sets the committee that attests the transfer events, the `threshold` of the hex-encoded Ethereum
+    /// `members` should sign the event.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn set_committee(&mut self, members: Vec<String>, threshold: u32) {
+        let mut committee = CommitteeConfig {
+            members: vec![],
+            threshold,
+        };
+        for member in members {
+            let member = get_eth_address(member);
+            require!(
+                !committee.members.contains(&member),
+                "Duplicated committee member"
+            );
+            committee.members.push(member);
+        }
+        require!(
+            threshold > 0 && threshold as usize <= committee.members.len(),
+            "The threshold should be between 1 and the number of the members"
+        );
+
+        let previous_committee = self.committee.replace(committee.clone());
+
+        FastBridgeEvent::FastBridgeSetCommitteeEvent {
+            previous_committee,
+            committee,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn get_committee(&self) -> Option<CommitteeConfig> {
+        self.committee.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attestation_hash() {
+        let event = EthTransferEvent {
+            eth_bridge_contract: get_eth_address(
+                "6b175474e89094c44da98b954eedeac495271d0f".to_string(),
+            ),
+            nonce: 1,
+            relayer: get_eth_address("1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string()),
+            token: get_eth_address("71c7656ec7ab88b098defb751b7401b5f6d8976f".to_string()),
+            recipient: get_eth_address("8ba1f109551bd432803012645ac136ddd64dba72".to_string()),
+            amount: 100,
+            filled_amount: None,
+            unlock_recipient: "danny".to_string(),
+            transfer_id: vec![0u8; 32].try_into().unwrap(),
+            payload_hash: None,
+            eth_block_height: None,
+        };
+
+        assert_eq!(
+            hex::encode(get_attestation_hash(&event)),
+            "4bcc52916a192ce8cee6ae1a36237a773167ed071eaf17727cb605f6fc5ac8fd"
+        );
+    }
+}
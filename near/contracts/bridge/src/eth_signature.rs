@@ -0,0 +1,103 @@
+use fast_bridge_common::EthAddress;
+use near_sdk::{env, AccountId};
+
+const ETH_SIGNATURE_LENGTH: usize = 65;
+
+/// Hashes the `message` the same way as `personal_sign` of the Ethereum wallets (EIP-191).
+pub fn personal_message_hash(message: &[u8]) -> Vec<u8> {
+    let prefix = format!("\x19Ethereum Signed Message:\n{}", message.len());
+    env::keccak256(&[prefix.as_bytes(), message].concat())
+}
+
+/// Hashes the typed structured data the same way as `eth_signTypedData` of the Ethereum wallets (EIP-712).
+pub fn typed_data_hash(domain_separator: &[u8], struct_hash: &[u8]) -> Vec<u8> {
+    env::keccak256(&[&b"\x19\x01"[..], domain_separator, struct_hash].concat())
+}
+
+/// Recovers the Ethereum address that produced the 65-byte `signature` (`r || s || v`) of the `hash`.
+pub fn recover_eth_address(hash: &[u8], signature: &[u8]) -> Option<EthAddress> {
+    if signature.len() != ETH_SIGNATURE_LENGTH {
+        return None;
+    }
+
+    let v = match signature[64] {
+        27 | 28 => signature[64] - 27,
+        0 | 1 => signature[64],
+        _ => return None,
+    };
+    let public_key = env::ecrecover(hash, &signature[..64], v, true)?;
+    let address = env::keccak256(&public_key)[12..].try_into().ok()?;
+
+    Some(EthAddress(address))
+}
+
+/// The message that the Ethereum relayer signs to claim its fill to the `recipient_id`.
+pub fn get_relayer_claim_message(
+    fast_bridge: &AccountId,
+    nonce: u128,
+    recipient_id: &AccountId,
+) -> String {
+    format!(
+        "Claim the transfer {} of the Fast Bridge `{}` to `{}`",
+        nonce, fast_bridge, recipient_id
+    )
+}
+
+/// The message that the Ethereum relayer signs to be declared by the LP `lp_account_id`.
+pub fn get_relayer_registration_message(
+    fast_bridge: &AccountId,
+    lp_account_id: &AccountId,
+    nonce: u64,
+) -> String {
+    format!(
+        "Register the relayer for the LP `{}` of the Fast Bridge `{}` with nonce {}",
+        lp_account_id, fast_bridge, nonce
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fast_bridge_common::get_eth_address;
+
+    #[test]
+    fn test_recover_relayer_claim_signer() {
+        let message = get_relayer_claim_message(
+            &"fast_bridge.near".parse().unwrap(),
+            1,
+            &"relayer.near".parse().unwrap(),
+        );
+        let hash = personal_message_hash(message.as_bytes());
+        assert_eq!(
+            hex::encode(&hash),
+            "eca7d355827661dd53edb0bc9ad07366ed0974f93a0c14d9b81c04163f77e394"
+        );
+
+        let signature = hex::decode("4e085163ee35bca3df5f44922939878fd5c213c27c9cd7047a3f6442fbc560023edc708dd03594e25324aeb9e7536625ef3741596b21b77755b427e841fb38771b").unwrap();
+        assert_eq!(
+            recover_eth_address(&hash, &signature),
+            Some(get_eth_address(
+                "7E5F4552091A69125d5DfCb7b8C2659029395Bdf".to_string()
+            ))
+        );
+        assert_eq!(recover_eth_address(&hash, &signature[..64]), None);
+    }
+
+    #[test]
+    fn test_recover_relayer_registration_signer() {
+        let message = get_relayer_registration_message(
+            &"alice_near".parse().unwrap(),
+            &"danny".parse().unwrap(),
+            0,
+        );
+        let hash = personal_message_hash(message.as_bytes());
+
+        let signature = hex::decode("7d2305484b71a074bb9f15ce24e84a77c212105444d605de20b7853e41608e0b76e7383a7c103419547272c588492e87e38af2359cbb2b9960a427e39faed8e71b").unwrap();
+        assert_eq!(
+            recover_eth_address(&hash, &signature),
+            Some(get_eth_address(
+                "7E5F4552091A69125d5DfCb7b8C2659029395Bdf".to_string()
+            ))
+        );
+    }
+}
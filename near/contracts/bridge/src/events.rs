@@ -0,0 +1,321 @@
+use fast_bridge_common::{EthAddress, TransferMessage};
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::{AccountId, Duration};
+
+use crate::aurora::AuroraConfig;
+use crate::committee::CommitteeConfig;
+use crate::integrator_fee::IntegratorFee;
+use crate::lp_registry::RegisteredLp;
+use crate::optimistic_claims::{OptimisticClaim, OptimisticClaimConfig};
+use crate::proof_verifier::ProofVerifier;
+use crate::quotes::Quote;
+use crate::reservations::{ReservationConfig, TransferReservation};
+use crate::swap::SwapReceipt;
+use crate::token_pairs::TokenPair;
+use crate::whitelist::WhitelistMode;
+use crate::TransferDetails;
+
+/// The NEP-297 standard name of the events emitted by this contract.
+pub const EVENT_STANDARD: &str = "fast-bridge";
+/// The version of the event schema. It only increases: the minor version is bumped on every change
+/// of the events payload, so the indexers can rely on the greater version being the newer schema.
+pub const EVENT_SCHEMA_VERSION: &str = "1.21.0";
+
+/// Events of the state changes that aren't covered by `fast_bridge_common::Event`.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+pub enum FastBridgeEvent {
+    FastBridgeInitTransferRequestEvent {
+        sender_id: AccountId,
+        transfer_message: TransferMessage,
+    },
+    FastBridgeInitTransferDetailsEvent {
+        nonce: U128,
+        transfer_details: TransferDetails,
+    },
+    FastBridgeLpPartialUnlockEvent {
+        nonce: U128,
+        recipient_id: AccountId,
+        amount: U128,
+        fee_amount: U128,
+        filled_amount_eth: U128,
+        transfer_message: TransferMessage,
+    },
+    FastBridgeReserveTransferEvent {
+        nonce: U128,
+        reservation: TransferReservation,
+    },
+    FastBridgeReleaseTransferReservationEvent {
+        nonce: U128,
+        reservation: TransferReservation,
+        recipient_id: AccountId,
+        is_slashed: bool,
+    },
+    FastBridgeOptimisticClaimEvent {
+        nonce: U128,
+        claim: OptimisticClaim,
+    },
+    FastBridgeChallengeOptimisticClaimEvent {
+        nonce: U128,
+        claim: OptimisticClaim,
+        challenger_id: AccountId,
+    },
+    FastBridgeReleaseOptimisticClaimEvent {
+        nonce: U128,
+        claim: OptimisticClaim,
+    },
+    FastBridgeFeeScheduleRefundEvent {
+        nonce: U128,
+        recipient_id: AccountId,
+        token: AccountId,
+        amount: U128,
+        eth_block_height: u64,
+    },
+    FastBridgeReleaseNativeFeeEvent {
+        nonce: U128,
+        recipient_id: AccountId,
+        amount: U128,
+        is_refund: bool,
+    },
+    FastBridgeWithdrawNativeFeeEvent {
+        account_id: AccountId,
+        amount: U128,
+    },
+    FastBridgeReleaseIntegratorFeeEvent {
+        nonce: U128,
+        recipient_id: AccountId,
+        integrator_fee: IntegratorFee,
+        is_refund: bool,
+    },
+    FastBridgeLpFeeRefundEvent {
+        nonce: U128,
+        lp_account: AccountId,
+        recipient_id: AccountId,
+        token: AccountId,
+        amount: U128,
+    },
+    FastBridgeForceUnlockEvent {
+        nonce: U128,
+        recipient_id: AccountId,
+        transfer_message: TransferMessage,
+        unlocked_by: AccountId,
+    },
+    FastBridgeTransferStorageDepositEvent {
+        account_id: AccountId,
+        amount: U128,
+    },
+    FastBridgeTransferStorageWithdrawEvent {
+        account_id: AccountId,
+        amount: U128,
+    },
+    FastBridgeSetProverAccountEvent {
+        prover_account: AccountId,
+    },
+    FastBridgeSetEthClientAccountEvent {
+        eth_client_account: AccountId,
+    },
+    FastBridgeSetEthBridgeContractEvent {
+        eth_bridge_contract: EthAddress,
+    },
+    FastBridgeSetLockTimeEvent {
+        lock_time_min: Duration,
+        lock_time_max: Duration,
+    },
+    FastBridgeSetTokenWhitelistModeEvent {
+        token: AccountId,
+        previous_mode: Option<WhitelistMode>,
+        mode: WhitelistMode,
+        changed_by: AccountId,
+    },
+    FastBridgeAddTokenToAccountWhitelistEvent {
+        token: Option<AccountId>,
+        account: AccountId,
+        was_whitelisted: bool,
+        changed_by: AccountId,
+    },
+    FastBridgeRemoveTokenFromAccountWhitelistEvent {
+        token: Option<AccountId>,
+        account: AccountId,
+        was_whitelisted: bool,
+        changed_by: AccountId,
+    },
+    FastBridgeSetWhitelistModeEnabledEvent {
+        previous_enabled: bool,
+        enabled: bool,
+        changed_by: AccountId,
+    },
+    FastBridgeAddAccountToBlocklistEvent {
+        account: AccountId,
+        was_blocked: bool,
+        changed_by: AccountId,
+    },
+    FastBridgeRemoveAccountFromBlocklistEvent {
+        account: AccountId,
+        was_blocked: bool,
+        changed_by: AccountId,
+    },
+    FastBridgeAddEthAddressToDenylistEvent {
+        address: EthAddress,
+        was_denied: bool,
+        changed_by: AccountId,
+    },
+    FastBridgeRemoveEthAddressFromDenylistEvent {
+        address: EthAddress,
+        was_denied: bool,
+        changed_by: AccountId,
+    },
+    FastBridgeSetTokenPairEvent {
+        token_near: AccountId,
+        previous_token_pair: Option<TokenPair>,
+        token_pair: TokenPair,
+        changed_by: AccountId,
+    },
+    FastBridgeRemoveTokenPairEvent {
+        token_near: AccountId,
+        previous_token_pair: Option<TokenPair>,
+        changed_by: AccountId,
+    },
+    FastBridgeSetTokenPairCheckEnabledEvent {
+        previous_enabled: bool,
+        enabled: bool,
+        changed_by: AccountId,
+    },
+    FastBridgeSetTransferReservationConfigEvent {
+        previous_config: Option<ReservationConfig>,
+        config: Option<ReservationConfig>,
+        changed_by: AccountId,
+    },
+    FastBridgeRegisterLpEvent {
+        account_id: AccountId,
+        lp: RegisteredLp,
+    },
+    FastBridgeUpdateLpEvent {
+        account_id: AccountId,
+        lp: RegisteredLp,
+    },
+    FastBridgeUnregisterLpEvent {
+        account_id: AccountId,
+        lp: RegisteredLp,
+    },
+    FastBridgeWithdrawLpStakeEvent {
+        account_id: AccountId,
+        token: AccountId,
+        amount: U128,
+    },
+    FastBridgeSlashLpStakeEvent {
+        nonce: U128,
+        lp_account: AccountId,
+        recipient_id: AccountId,
+        token: AccountId,
+        amount: U128,
+    },
+    FastBridgeSetLpStakeTokenEvent {
+        token: AccountId,
+        previous_min_stake: Option<U128>,
+        min_stake: Option<U128>,
+        changed_by: AccountId,
+    },
+    FastBridgeSetLpRegistryEnabledEvent {
+        previous_enabled: bool,
+        enabled: bool,
+        changed_by: AccountId,
+    },
+    FastBridgeLpBridgeBackEvent {
+        sender_id: AccountId,
+        token: AccountId,
+        amount: U128,
+        eth_address: EthAddress,
+    },
+    FastBridgeSetProofVerifierEvent {
+        token: Option<AccountId>,
+        previous_verifier: Option<ProofVerifier>,
+        verifier: Option<ProofVerifier>,
+        changed_by: AccountId,
+    },
+    FastBridgeSetCommitteeEvent {
+        previous_committee: Option<CommitteeConfig>,
+        committee: CommitteeConfig,
+        changed_by: AccountId,
+    },
+    FastBridgeSetOptimisticClaimConfigEvent {
+        previous_config: Option<OptimisticClaimConfig>,
+        config: Option<OptimisticClaimConfig>,
+        changed_by: AccountId,
+    },
+    FastBridgePublishQuoteEvent {
+        quote_id: U128,
+        quote: Quote,
+    },
+    FastBridgeCancelQuoteEvent {
+        quote_id: U128,
+        quote: Quote,
+    },
+    FastBridgeRemoveQuoteEvent {
+        quote_id: U128,
+        quote: Quote,
+    },
+    FastBridgeBindTransferToQuoteEvent {
+        nonce: U128,
+        quote_id: U128,
+        reservation: TransferReservation,
+        remaining_capacity: U128,
+    },
+    FastBridgeSwapEvent {
+        sender_id: AccountId,
+        token_in: AccountId,
+        amount_in: U128,
+        token_out: AccountId,
+        amount_out: U128,
+    },
+    FastBridgeSwapFailedEvent {
+        sender_id: AccountId,
+        token_in: AccountId,
+        amount_in: U128,
+        refund_amount: U128,
+    },
+    FastBridgeSwapWithdrawFailedEvent {
+        receipt_id: U128,
+        receipt: SwapReceipt,
+    },
+    FastBridgeSetDexAccountEvent {
+        previous_dex_account: Option<AccountId>,
+        dex_account: Option<AccountId>,
+        changed_by: AccountId,
+    },
+    FastBridgeSetMaxIntegratorFeeBpsEvent {
+        previous_max_fee_bps: u32,
+        max_fee_bps: u32,
+        changed_by: AccountId,
+    },
+    FastBridgeSetAuroraConfigEvent {
+        previous_config: Option<AuroraConfig>,
+        config: Option<AuroraConfig>,
+        changed_by: AccountId,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a FastBridgeEvent,
+}
+
+impl FastBridgeEvent {
+    pub fn emit(&self) {
+        let event_log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_SCHEMA_VERSION,
+            event: self,
+        };
+        near_sdk::env::log_str(&format!(
+            "EVENT_JSON:{}",
+            near_sdk::serde_json::to_string(&event_log).unwrap()
+        ));
+    }
+}
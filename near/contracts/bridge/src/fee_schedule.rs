@@ -0,0 +1,115 @@
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+use crate::*;
+
+/// The Dutch auction of the fee: the LP gets the whole `fee.amount` of the transfer for the fill at the
+/// `start_block_height` and the fee decays linearly to the `end_fee_amount` at the `valid_till_block_height`.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeeSchedule {
+    pub end_fee_amount: U128,
+    /// The estimated Ethereum block height at the initialization of the transfer
+    pub start_block_height: u64,
+}
+
+/// Gets the fee of the fill at the `eth_block_height` for the `start_fee_amount` decaying by the `fee_schedule`
+/// till the `end_block_height`.
+pub fn get_scheduled_fee_amount(
+    start_fee_amount: u128,
+    fee_schedule: &FeeSchedule,
+    end_block_height: u64,
+    eth_block_height: u64,
+) -> u128 {
+    if eth_block_height <= fee_schedule.start_block_height {
+        return start_fee_amount;
+    }
+    if eth_block_height >= end_block_height {
+        return fee_schedule.end_fee_amount.0;
+    }
+
+    let fee_decay = start_fee_amount - fee_schedule.end_fee_amount.0;
+    start_fee_amount
+        - utils::mul_div(
+            fee_decay,
+            (eth_block_height - fee_schedule.start_block_height).into(),
+            (end_block_height - fee_schedule.start_block_height).into(),
+        )
+}
+
+impl FastBridge {
+    /// Refunds the part of the `fee_amount` that decayed by the block of the fill to the `originator_id`,
+    /// if the transfer has the fee schedule. The fill block that isn't known from the proof is settled
+    /// as the end of the schedule, so the LP gets the `end_fee_amount`.
+    ///
+    /// Returns the part of the `fee_amount` that is paid to the LP.
+    pub(crate) fn apply_fee_schedule(
+        &mut self,
+        transfer_id: &String,
+        originator_id: &AccountId,
+        transfer_message: &TransferMessage,
+        proof: &EthTransferEvent,
+        fee_amount: u128,
+    ) -> u128 {
+        let Some(fee_schedule) = self
+            .pending_transfers_details
+            .get(transfer_id)
+            .and_then(|transfer_details| transfer_details.fee_schedule)
+        else {
+            return fee_amount;
+        };
+        let start_fee_amount = transfer_message.fee.amount.0;
+        if start_fee_amount == 0 {
+            return fee_amount;
+        }
+
+        let end_block_height = transfer_message
+            .valid_till_block_height
+            .unwrap_or_else(|| env::panic_str("The transfer has no valid till block height"));
+        let eth_block_height = proof.eth_block_height.unwrap_or(end_block_height);
+        let scheduled_fee_amount = get_scheduled_fee_amount(
+            start_fee_amount,
+            &fee_schedule,
+            end_block_height,
+            eth_block_height,
+        );
+
+        let lp_fee_amount = utils::mul_div(fee_amount, scheduled_fee_amount, start_fee_amount);
+        let refund_amount = fee_amount - lp_fee_amount;
+        if refund_amount > 0 {
+            self.increase_balance(originator_id, &transfer_message.fee.token, &refund_amount);
+
+            FastBridgeEvent::FastBridgeFeeScheduleRefundEvent {
+                nonce: U128(proof.nonce),
+                recipient_id: originator_id.clone(),
+                token: transfer_message.fee.token.clone(),
+                amount: U128(refund_amount),
+                eth_block_height,
+            }
+            .emit();
+        }
+
+        lp_fee_amount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scheduled_fee_amount() {
+        let fee_schedule = FeeSchedule {
+            end_fee_amount: U128(20),
+            start_block_height: 100,
+        };
+
+        assert_eq!(get_scheduled_fee_amount(100, &fee_schedule, 200, 50), 100);
+        assert_eq!(get_scheduled_fee_amount(100, &fee_schedule, 200, 100), 100);
+        assert_eq!(get_scheduled_fee_amount(100, &fee_schedule, 200, 125), 80);
+        assert_eq!(get_scheduled_fee_amount(100, &fee_schedule, 200, 150), 60);
+        assert_eq!(get_scheduled_fee_amount(100, &fee_schedule, 200, 200), 20);
+        assert_eq!(get_scheduled_fee_amount(100, &fee_schedule, 200, 300), 20);
+    }
+}
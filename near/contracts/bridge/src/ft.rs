@@ -9,13 +9,17 @@ impl FungibleTokenReceiver for FastBridge {
     ///
     /// This function is called when the smart contract receives tokens from a sender. If `msg` is not empty, the function decodes the `msg` parameter, which is a `TransferMessage` in borsh Base64 format, and uses it to initiate a token transfer to Ethereum. Otherwise, the function treats it as a deposit action, increases the balance of the sender, and emits a `FastBridgeDepositEvent`.
     ///
+    /// The `msg` that starts with `swap:` is followed by the `SwapAndTransferMessage` in borsh Base64 format. The tokens of any token contract are swapped on the configured DEX to the token of the transfer, which is initialized with the swap output. The tokens are returned to the sender if the swap fails.
+    ///
     /// Note that this function overrides a standard NEP-141 implementation of `ft_on_transfer()` so the arguments of the function are the same.
+    /// The storage bond of the initiated transfer is taken from the storage balance deposited in advance with `transfer_storage_deposit()`.
     ///
     /// # Arguments
     ///
     /// * `sender_id` - The account ID of the sender.
     /// * `amount` - The amount of tokens being transferred.
-    /// * `msg` - The transfer message in borsh Base64 format.
+    /// * `msg` - The transfer message in borsh Base64 format, optionally followed by the borsh-encoded `TransferOptions`,
+    ///   or the swap-and-transfer message prefixed with `swap:`.
     #[pause]
     fn ft_on_transfer(
         &mut self,
@@ -24,15 +28,20 @@ impl FungibleTokenReceiver for FastBridge {
         msg: String,
     ) -> PromiseOrValue<U128> {
         let token_account_id = env::predecessor_account_id();
+        if let Some(swap_msg) = msg.strip_prefix(swap::SWAP_MESSAGE_PREFIX) {
+            // The whitelist applies to the swap output, the input may be any token
+            self.check_account_not_blocked(&sender_id);
+            return self
+                .swap_and_init_transfer(token_account_id, sender_id, amount, swap_msg)
+                .into();
+        }
+
         self.check_whitelist_token_and_account(&token_account_id, &sender_id);
 
         if !msg.is_empty() {
             let decoded_base64 =
                 base64::decode(&msg).unwrap_or_else(|_| env::panic_str("Invalid base64 message"));
-            let transfer_message =
-                TransferMessage::try_from_slice(&decoded_base64).unwrap_or_else(|_| {
-                    env::panic_str("Invalid borsh format of the `TransferMessage`")
-                });
+            let (transfer_message, transfer_options) = decode_transfer_message(&decoded_base64);
 
             let update_balance = UpdateBalance {
                 sender_id: sender_id.clone(),
@@ -40,8 +49,13 @@ impl FungibleTokenReceiver for FastBridge {
                 amount,
             };
 
-            self.init_transfer_internal(transfer_message, sender_id, Some(update_balance))
-                .into()
+            self.init_transfer_internal(
+                transfer_message,
+                transfer_options,
+                sender_id,
+                Some(update_balance),
+            )
+            .into()
         } else {
             self.increase_balance(&sender_id, &token_account_id, &amount.0);
 
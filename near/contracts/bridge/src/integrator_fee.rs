@@ -0,0 +1,161 @@
+use near_plugins::access_control_any;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId};
+
+use crate::*;
+
+/// The fee of the wallet or the dApp that integrated the bridge, paid in the transfer token.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntegratorFee {
+    pub account_id: AccountId,
+    pub amount: U128,
+}
+
+/// The transfers of the token that were settled by LPs with the fee of the integrator.
+#[derive(
+    Default, BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IntegratorStats {
+    pub transfer_count: u64,
+    /// The sum of the transfer amounts
+    pub volume: U128,
+    /// The sum of the fees paid to the integrator
+    pub fee_amount: U128,
+}
+
+fn get_integrator_stats_key(account_id: &AccountId, token: &AccountId) -> String {
+    format!("{}:{}", account_id, token)
+}
+
+#[near_bindgen]
+impl FastBridge {
+    /// Sets the maximal integrator fee in basis points of the transfer amount, zero disables the integrator fees.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn set_max_integrator_fee_bps(&mut self, max_fee_bps: u32) {
+        require!(
+            max_fee_bps <= utils::MAX_FEE_BPS,
+            format!("The fee can't exceed {} basis points", utils::MAX_FEE_BPS)
+        );
+        let previous_max_fee_bps = std::mem::replace(&mut self.max_integrator_fee_bps, max_fee_bps);
+
+        FastBridgeEvent::FastBridgeSetMaxIntegratorFeeBpsEvent {
+            previous_max_fee_bps,
+            max_fee_bps,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn get_max_integrator_fee_bps(&self) -> u32 {
+        self.max_integrator_fee_bps
+    }
+
+    pub fn get_integrator_stats(&self, account_id: AccountId, token: AccountId) -> IntegratorStats {
+        self.integrator_stats
+            .get(&get_integrator_stats_key(&account_id, &token))
+            .unwrap_or_default()
+    }
+}
+
+impl FastBridge {
+    /// Takes the integrator fee of the new transfer from the balance of the `sender_id`.
+    pub(crate) fn lock_integrator_fee(
+        &mut self,
+        sender_id: &AccountId,
+        transfer_message: &TransferMessage,
+        integrator_fee: &IntegratorFee,
+    ) {
+        self.check_account_not_blocked(&integrator_fee.account_id);
+        let max_fee_amount = utils::mul_div(
+            transfer_message.transfer.amount.0,
+            self.max_integrator_fee_bps.into(),
+            utils::MAX_FEE_BPS.into(),
+        );
+        require!(
+            integrator_fee.amount.0 <= max_fee_amount,
+            format!(
+                "The integrator fee can't exceed {} of the transfer token",
+                max_fee_amount
+            )
+        );
+
+        let token = &transfer_message.transfer.token_near;
+        require!(
+            self.get_user_balance(sender_id, token) >= integrator_fee.amount,
+            "Not enough transfer token balance for the integrator fee."
+        );
+        self.decrease_balance(sender_id, token, &integrator_fee.amount.0);
+    }
+
+    /// Credits the integrator fee of the transfer settled by the LP to the integrator.
+    pub(crate) fn pay_integrator_fee(
+        &mut self,
+        transfer_id: &String,
+        transfer_message: &TransferMessage,
+    ) {
+        let Some(integrator_fee) = self.get_transfer_integrator_fee(transfer_id) else {
+            return;
+        };
+
+        let token = &transfer_message.transfer.token_near;
+        self.increase_balance(&integrator_fee.account_id, token, &integrator_fee.amount.0);
+
+        let stats_key = get_integrator_stats_key(&integrator_fee.account_id, token);
+        let mut stats = self.integrator_stats.get(&stats_key).unwrap_or_default();
+        stats.transfer_count += 1;
+        stats.volume = U128(stats.volume.0 + transfer_message.transfer.amount.0);
+        stats.fee_amount = U128(stats.fee_amount.0 + integrator_fee.amount.0);
+        self.integrator_stats.insert(&stats_key, &stats);
+
+        FastBridgeEvent::FastBridgeReleaseIntegratorFeeEvent {
+            nonce: U128(transfer_id.parse().unwrap()),
+            recipient_id: integrator_fee.account_id.clone(),
+            integrator_fee,
+            is_refund: false,
+        }
+        .emit();
+    }
+
+    /// Refunds the integrator fee of the unlocked transfer to the balance of the `recipient_id`.
+    pub(crate) fn refund_integrator_fee(
+        &mut self,
+        transfer_id: &String,
+        transfer_message: &TransferMessage,
+        recipient_id: &AccountId,
+    ) {
+        let Some(integrator_fee) = self.get_transfer_integrator_fee(transfer_id) else {
+            return;
+        };
+
+        self.increase_balance(
+            recipient_id,
+            &transfer_message.transfer.token_near,
+            &integrator_fee.amount.0,
+        );
+
+        FastBridgeEvent::FastBridgeReleaseIntegratorFeeEvent {
+            nonce: U128(transfer_id.parse().unwrap()),
+            recipient_id: recipient_id.clone(),
+            integrator_fee,
+            is_refund: true,
+        }
+        .emit();
+    }
+
+    /// Gets the integrator fee that is refunded if the pending transfer is unlocked.
+    pub(crate) fn get_integrator_fee_refund(&self, transfer_id: &String) -> U128 {
+        self.get_transfer_integrator_fee(transfer_id)
+            .map_or(U128(0), |integrator_fee| integrator_fee.amount)
+    }
+
+    fn get_transfer_integrator_fee(&self, transfer_id: &String) -> Option<IntegratorFee> {
+        self.pending_transfers_details
+            .get(transfer_id)
+            .and_then(|transfer_details| transfer_details.integrator_fee)
+    }
+}
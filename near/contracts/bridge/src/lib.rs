@@ -1,3 +1,4 @@
+use crate::events::FastBridgeEvent;
 use crate::lp_relayer::EthTransferEvent;
 use fast_bridge_common::*;
 use near_plugins::{
@@ -20,8 +21,27 @@ use whitelist::WhitelistMode;
 
 pub use crate::ft::*;
 
+mod aurora;
+mod blocklist;
+mod bridge_back;
+mod committee;
+mod eth_signature;
+mod events;
+mod fee_schedule;
 mod ft;
+mod integrator_fee;
+mod lp_registry;
 mod lp_relayer;
+mod migration;
+mod native_fee;
+mod optimistic_claims;
+mod partial_fills;
+mod proof_verifier;
+mod quotes;
+mod reservations;
+mod swap;
+mod token_pairs;
+mod transfer_storage;
 mod utils;
 mod whitelist;
 
@@ -94,7 +114,60 @@ trait FastBridgeInterface {
         #[serializer(borsh)]
         verification_success: bool,
         #[serializer(borsh)] proof: EthTransferEvent,
+    ) -> TransferMessage;
+    fn lp_unlock_and_withdraw_callback(
+        &mut self,
+        #[callback]
+        #[serializer(borsh)]
+        transfer_message: TransferMessage,
+        #[serializer(borsh)] sender_id: AccountId,
+        #[serializer(borsh)] msg: Option<String>,
+    ) -> Promise;
+    fn lp_unlock_and_bridge_back_callback(
+        &mut self,
+        #[callback]
+        #[serializer(borsh)]
+        transfer_message: TransferMessage,
+        #[serializer(borsh)] sender_id: AccountId,
+        #[serializer(borsh)] eth_address: EthAddress,
     ) -> Promise;
+    fn bridge_back_callback(
+        &mut self,
+        token_id: AccountId,
+        amount: U128,
+        sender_id: AccountId,
+        eth_address: EthAddress,
+    ) -> U128;
+    fn swap_callback(
+        &mut self,
+        #[serializer(borsh)] sender_id: AccountId,
+        #[serializer(borsh)] token_in: AccountId,
+        #[serializer(borsh)] amount_in: U128,
+        #[serializer(borsh)] dex_account: AccountId,
+        #[serializer(borsh)] receipt_id: U128,
+        #[serializer(borsh)] swap_message: swap::SwapAndTransferMessage,
+    ) -> PromiseOrValue<U128>;
+    fn swap_receipt_callback(
+        &mut self,
+        #[serializer(borsh)] receipt_id: U128,
+        #[serializer(borsh)] receipt: swap::SwapReceipt,
+        #[serializer(borsh)] swap_message: Option<swap::SwapAndTransferMessage>,
+    ) -> PromiseOrValue<U128>;
+    fn swap_withdraw_callback(
+        &mut self,
+        #[serializer(borsh)] receipt_id: U128,
+        #[serializer(borsh)] receipt: swap::SwapReceipt,
+        #[serializer(borsh)] swap_message: Option<swap::SwapAndTransferMessage>,
+    ) -> U128;
+    fn challenge_optimistic_claim_callback(
+        &mut self,
+        #[callback]
+        #[serializer(borsh)]
+        verification_result: bool,
+        #[serializer(borsh)] nonce: U128,
+        #[serializer(borsh)] claim: optimistic_claims::OptimisticClaim,
+        #[serializer(borsh)] challenger_id: AccountId,
+    );
     fn unlock_callback(
         &mut self,
         #[callback]
@@ -107,6 +180,7 @@ trait FastBridgeInterface {
         #[serializer(borsh)] transfer_message: TransferMessage,
         #[serializer(borsh)] sender_id: AccountId,
         #[serializer(borsh)] update_balance: Option<UpdateBalance>,
+        #[serializer(borsh)] transfer_options: TransferOptions,
     ) -> PromiseOrValue<U128>;
     fn unlock_and_withdraw_callback(
         &mut self,
@@ -116,6 +190,7 @@ trait FastBridgeInterface {
         #[serializer(borsh)] sender_id: AccountId,
         #[serializer(borsh)] recipient_id: Option<AccountId>,
         #[serializer(borsh)] aurora_native_token_account_id: Option<AccountId>,
+        #[serializer(borsh)] integrator_fee_refund: U128,
     ) -> Promise;
 
     fn unlock_and_withdraw_return_transfer_msg(
@@ -143,6 +218,117 @@ pub struct UpdateBalance {
     amount: U128,
 }
 
+/// The data of the pending transfer that isn't a part of the `TransferMessage`.
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferDetails {
+    /// The amount of the Ethereum token that should be transferred to the recipient, in the Ethereum token decimals
+    pub amount_eth: U128,
+    /// The progress of the partial fills, `None` if the transfer can be filled only completely
+    pub partial_fill: Option<PartialFill>,
+    /// The decay of the fee by the block of the fill, `None` if the fee is fixed
+    pub fee_schedule: Option<fee_schedule::FeeSchedule>,
+    /// The fee in native NEAR that is paid to the LP in addition to or instead of the `fee`, `None` if there is no such fee
+    pub native_fee_amount: Option<U128>,
+    /// The fee of the integrator that is paid on the settlement by the LP, `None` if there is no such fee
+    pub integrator_fee: Option<integrator_fee::IntegratorFee>,
+    /// The hash of the call data the recipient should be called with on Ethereum, `None` if there is no payload
+    pub payload_hash: Option<[u8; 32]>,
+}
+
+/// The part of the transfer that was filled on Ethereum and credited to the LPs.
+#[derive(
+    Default, Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PartialFill {
+    /// The sum of the claimed fills, in the Ethereum token decimals
+    pub filled_amount_eth: U128,
+    /// The part of the transfer amount that was credited to the LPs
+    pub unlocked_amount: U128,
+    /// The part of the fee amount that was credited to the LPs
+    pub unlocked_fee_amount: U128,
+    /// The total filled amounts reported by the claimed fills, each fill can be claimed only once.
+    /// The number of the fills is bounded by `partial_fills::MAX_PARTIAL_FILLS`.
+    pub claimed_fills: Vec<U128>,
+}
+
+/// The optional settings of the transfer that can be appended to the borsh-encoded `TransferMessage`.
+#[derive(
+    Default, Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferOptions {
+    /// Whether the transfer can be filled by several LPs with `transferTokensPartially` on Ethereum
+    pub allow_partial_fills: bool,
+    /// The fee that the LP gets for the fill at the `valid_till_block_height`. The fee decays linearly
+    /// to it from the `fee.amount` over the lock period and the rest is refunded to the originator.
+    /// `None` if the fee doesn't depend on the fill block.
+    pub end_fee_amount: Option<U128>,
+    /// The quote of the LP that gets the exclusive fill window of the transfer, see `publish_quote()`
+    pub quote_id: Option<U128>,
+    /// The fee in native NEAR that is taken from the storage balance of the sender, see `init_transfer()`.
+    /// The `fee.amount` of the `TransferMessage` can be zero to pay the LP in native NEAR only.
+    /// It isn't supported by the transfers that allow partial fills.
+    pub native_fee_amount: Option<U128>,
+    /// The fee of the wallet or the dApp that integrated the bridge, in the transfer token. It's bounded
+    /// by `get_max_integrator_fee_bps()` and isn't supported by the transfers that allow partial fills.
+    pub integrator_fee: Option<integrator_fee::IntegratorFee>,
+    /// The keccak256 hash of the call data the recipient contract should be called with on arrival. The hash
+    /// is a part of the transfer id, so the transfer should be filled with `transferTokensWithPayload` on Ethereum.
+    /// It isn't supported by the transfers that allow partial fills.
+    pub payload_hash: Option<[u8; 32]>,
+}
+
+/// Decodes the `TransferMessage` optionally followed by the `TransferOptions`.
+fn decode_transfer_message(data: &[u8]) -> (TransferMessage, TransferOptions) {
+    let mut data = data;
+    let transfer_message = TransferMessage::deserialize(&mut data)
+        .unwrap_or_else(|_| env::panic_str("Invalid borsh format of the `TransferMessage`"));
+    let transfer_options = if data.is_empty() {
+        TransferOptions::default()
+    } else {
+        TransferOptions::try_from_slice(data)
+            .unwrap_or_else(|_| env::panic_str("Invalid borsh format of the `TransferOptions`"))
+    };
+
+    (transfer_message, transfer_options)
+}
+
+/// Gets the non-zero amounts of the transfer and the fee tokens, summed up if they're the same token.
+fn get_unlocked_token_amounts(transfer_message: &TransferMessage) -> Vec<(AccountId, u128)> {
+    let token = &transfer_message.transfer.token_near;
+    let fee_token = &transfer_message.fee.token;
+    let amounts = if fee_token == token {
+        vec![(
+            token.clone(),
+            transfer_message.transfer.amount.0 + transfer_message.fee.amount.0,
+        )]
+    } else {
+        vec![
+            (token.clone(), transfer_message.transfer.amount.0),
+            (fee_token.clone(), transfer_message.fee.amount.0),
+        ]
+    };
+
+    amounts
+        .into_iter()
+        .filter(|(_, amount)| *amount > 0)
+        .collect()
+}
+
+/// Checks that the caller is the NEAR `unlock_recipient` of the event and returns its account.
+fn get_caller_as_unlock_recipient(parsed_proof: &EthTransferEvent) -> AccountId {
+    let caller_id = env::predecessor_account_id();
+    require!(
+        lp_relayer::UnlockRecipient::parse(&parsed_proof.unlock_recipient)
+            == Some(lp_relayer::UnlockRecipient::Near(caller_id.clone())),
+        "Only the unlock recipient can withdraw the unlocked tokens"
+    );
+
+    caller_id
+}
+
 #[derive(BorshSerialize, BorshStorageKey)]
 enum StorageKey {
     PendingTransfers,
@@ -151,6 +337,30 @@ enum StorageKey {
     WhitelistTokens,
     WhitelistAccounts,
     PendingTransfersBalances,
+    TransferStorageBalances,
+    TransferStorageBonds,
+    BlockedAccounts,
+    EthAddressDenylist,
+    TokenPairs,
+    PendingTransfersDetails,
+    TransferReservations,
+    RegisteredLps,
+    LpEthRelayers,
+    LpStakeTokens,
+    TokenProofVerifiers,
+    OptimisticClaims,
+    Quotes,
+    IntegratorStats,
+    LpUnbondings,
+    TokenQuotes,
+    TokenQuotesPrefix,
+    LpQuotes,
+    LpQuotesPrefix,
+    AccountWhitelistTokens,
+    AccountWhitelistTokensPrefix,
+    NativeFeeBalances,
+    SwapReceipts,
+    EthRelayerNonces,
 }
 
 #[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
@@ -180,6 +390,8 @@ pub enum Role {
     CodeStager,
     CodeDeployer,
     DurationManager,
+    /// May add and remove accounts from the blocklist.
+    BlocklistManager,
 }
 
 #[access_control(role_type(Role))]
@@ -209,6 +421,72 @@ pub struct FastBridge {
     /// The mode of the whitelist check
     is_whitelist_mode_enabled: bool,
     pending_transfers_balances: UnorderedMap<AccountId, u128>,
+    /// Mapping pending transfers to their details that aren't a part of the `TransferMessage`
+    pending_transfers_details: LookupMap<String, TransferDetails>,
+    /// Mapping accounts to the NEAR deposited to cover the storage of their pending transfers
+    transfer_storage_balances: LookupMap<AccountId, u128>,
+    /// Mapping pending transfers to the storage bonds locked by them
+    transfer_storage_bonds: LookupMap<String, u128>,
+    /// Accounts that are denied to use the bridge regardless of the whitelist mode
+    blocked_accounts: UnorderedSet<AccountId>,
+    /// Ethereum addresses that can't be used as a recipient or a token of the transfer
+    eth_address_denylist: UnorderedSet<EthAddress>,
+    /// Mapping NEAR tokens to their Ethereum counterparts
+    token_pairs: UnorderedMap<AccountId, token_pairs::TokenPair>,
+    /// Whether every transferred token is required to have a registered pair
+    is_token_pair_check_enabled: bool,
+    /// Mapping pending transfers to the exclusive fill windows reserved by LPs
+    transfer_reservations: LookupMap<String, reservations::TransferReservation>,
+    /// The settings of the transfer reservations, `None` if the reservations are disabled
+    transfer_reservation_config: Option<reservations::ReservationConfig>,
+    /// Mapping LP accounts to their stakes and Ethereum relayers
+    registered_lps: UnorderedMap<AccountId, lp_registry::RegisteredLp>,
+    /// Mapping Ethereum relayers to the LP accounts that declared them
+    lp_eth_relayers: LookupMap<EthAddress, AccountId>,
+    /// Mapping tokens allowed for the LP stakes to the minimal stake
+    lp_stake_tokens: UnorderedMap<AccountId, u128>,
+    /// Whether the fees are paid only to the registered LPs
+    is_lp_registry_enabled: bool,
+    /// The Aurora deployment for the LP claims to Aurora addresses, `None` if such claims are disabled
+    aurora_config: Option<aurora::AuroraConfig>,
+    /// The default verifier of the Ethereum proofs
+    proof_verifier: proof_verifier::ProofVerifier,
+    /// Mapping tokens to the verifiers of their proofs that override the default one
+    token_proof_verifiers: UnorderedMap<AccountId, proof_verifier::ProofVerifier>,
+    /// The committee that attests the transfer events for the tokens with the `Committee` verifier
+    committee: Option<committee::CommitteeConfig>,
+    /// Mapping pending transfers to the optimistic claims of LPs that wait for the challenge window
+    optimistic_claims: LookupMap<String, optimistic_claims::OptimisticClaim>,
+    /// The settings of the optimistic claims, `None` if the claims are disabled
+    optimistic_claim_config: Option<optimistic_claims::OptimisticClaimConfig>,
+    /// Mapping quote ids to the fee quotes published by LPs
+    quotes: UnorderedMap<String, quotes::Quote>,
+    /// The id of the last published quote
+    quote_nonce: u128,
+    /// The DEX that swaps the tokens of the swap-and-transfer messages, `None` if the swaps are disabled
+    dex_account: Option<AccountId>,
+    /// The total native NEAR fee locked by the pending transfers
+    pending_native_fee_balance: u128,
+    /// The maximal integrator fee in basis points of the transfer amount
+    max_integrator_fee_bps: u32,
+    /// Mapping integrators to their stats by using combined key {integrator}:{token}
+    integrator_stats: LookupMap<String, integrator_fee::IntegratorStats>,
+    /// Mapping unregistered LP accounts to their stakes that are unbonding
+    lp_unbondings: LookupMap<AccountId, lp_registry::LpUnbonding>,
+    /// Mapping tokens to the ids of their quotes
+    token_quotes: LookupMap<AccountId, UnorderedSet<String>>,
+    /// Mapping LP accounts to the ids of their quotes
+    lp_quotes: LookupMap<AccountId, UnorderedSet<String>>,
+    /// Mapping accounts to the tokens for which they are whitelisted with the combined key {token}:{account}
+    account_whitelist_tokens: LookupMap<AccountId, UnorderedSet<AccountId>>,
+    /// Mapping LP accounts to the native NEAR fees credited to them
+    native_fee_balances: LookupMap<AccountId, u128>,
+    /// The id of the last swap receipt, see `swap_and_init_transfer()`
+    swap_nonce: u128,
+    /// Mapping swap receipt ids to the swap outputs that failed to be withdrawn from the DEX
+    swap_receipts: LookupMap<String, swap::SwapReceipt>,
+    /// Mapping Ethereum relayers to the nonce of their next declaration by an LP, see `register_lp()`
+    eth_relayer_nonces: LookupMap<EthAddress, u64>,
 }
 
 #[near_bindgen]
@@ -241,7 +519,7 @@ impl FastBridge {
             "Error initialize: lock_time_min must be less than lock_time_max"
         );
 
-        let mut contract = Self {
+        let mut contract: Self = migration::FastBridgeV0 {
             pending_transfers: UnorderedMap::new(StorageKey::PendingTransfers),
             pending_transfers_balances: UnorderedMap::new(StorageKey::PendingTransfersBalances),
             token_balances: LookupMap::new(StorageKey::TokenBalances),
@@ -257,7 +535,8 @@ impl FastBridge {
             whitelist_tokens: UnorderedMap::new(StorageKey::WhitelistTokens),
             whitelist_accounts: UnorderedSet::new(StorageKey::WhitelistAccounts),
             is_whitelist_mode_enabled: whitelist_mode,
-        };
+        }
+        .into();
 
         near_sdk::require!(
             contract.acl_init_super_admin(near_sdk::env::predecessor_account_id()),
@@ -273,31 +552,52 @@ impl FastBridge {
     /// # Arguments
     ///
     /// * `msg` -- the encoded `TransferMessage` in borsh Base64 format. It contains details about the transaction - `token`, `fee_token`, `amount`, `recipient`, etc.
+    ///    The `TransferMessage` may be followed by the borsh-encoded `TransferOptions`.
+    ///
+    /// The attached deposit is added to the storage balance of the caller, which covers the storage bond of the transfer
+    /// and the `native_fee_amount` of the `TransferOptions`.
+    #[payable]
     #[pause]
     pub fn init_transfer(
         &mut self,
         msg: near_sdk::json_types::Base64VecU8,
     ) -> PromiseOrValue<U128> {
-        let transfer_message = TransferMessage::try_from_slice(&msg.0)
-            .unwrap_or_else(|_| env::panic_str("Invalid borsh format of the `TransferMessage`"));
-        self.init_transfer_internal(transfer_message, env::predecessor_account_id(), None)
+        let (transfer_message, transfer_options) = decode_transfer_message(&msg.0);
+        let sender_id = env::predecessor_account_id();
+        let attached_deposit = env::attached_deposit();
+        if attached_deposit > 0 {
+            self.increase_transfer_storage_balance(&sender_id, attached_deposit);
+        }
+
+        self.init_transfer_internal(transfer_message, transfer_options, sender_id, None)
             .into()
     }
 
     fn init_transfer_internal(
         &mut self,
         transfer_message: TransferMessage,
+        transfer_options: TransferOptions,
         sender_id: AccountId,
         update_balance: Option<UpdateBalance>,
     ) -> Promise {
-        near_sdk::env::log_str(&near_sdk::serde_json::to_string(&transfer_message).unwrap());
+        FastBridgeEvent::FastBridgeInitTransferRequestEvent {
+            sender_id: sender_id.clone(),
+            transfer_message: transfer_message.clone(),
+        }
+        .emit();
+
         ext_eth_client::ext(self.eth_client_account.clone())
             .with_static_gas(utils::tera_gas(5))
             .last_block_number()
             .then(
                 ext_self::ext(env::current_account_id())
                     .with_static_gas(utils::tera_gas(50))
-                    .init_transfer_callback(transfer_message, sender_id, update_balance),
+                    .init_transfer_callback(
+                        transfer_message,
+                        sender_id,
+                        update_balance,
+                        transfer_options,
+                    ),
             )
     }
 
@@ -320,6 +620,8 @@ impl FastBridge {
     ///
     /// * `update_balance` -- balance update in case the transfer of tokens and initialization of the transfer
     ///    happen in one transaction. The `UpdateBalance` is deserialized from a Borsh-encoded string.
+    ///
+    /// * `transfer_options` -- the optional settings of the transfer, e.g. whether it can be filled partially.
     #[private]
     pub fn init_transfer_callback(
         &mut self,
@@ -329,6 +631,7 @@ impl FastBridge {
         #[serializer(borsh)] transfer_message: TransferMessage,
         #[serializer(borsh)] sender_id: AccountId,
         #[serializer(borsh)] update_balance: Option<UpdateBalance>,
+        #[serializer(borsh)] transfer_options: TransferOptions,
     ) -> U128 {
         #[cfg(feature = "disable_different_fee_token")]
         require!(
@@ -356,6 +659,41 @@ impl FastBridge {
 
         self.validate_transfer_message(&transfer_message, &sender_id);
 
+        let transfer_details = TransferDetails {
+            amount_eth: U128(self.get_transfer_amount_eth(&transfer_message.transfer)),
+            partial_fill: transfer_options
+                .allow_partial_fills
+                .then(PartialFill::default),
+            fee_schedule: transfer_options.end_fee_amount.map(|end_fee_amount| {
+                require!(
+                    end_fee_amount <= transfer_message.fee.amount,
+                    "The end fee amount can't exceed the fee amount"
+                );
+                fee_schedule::FeeSchedule {
+                    end_fee_amount,
+                    start_block_height: last_block_height,
+                }
+            }),
+            native_fee_amount: transfer_options.native_fee_amount,
+            integrator_fee: transfer_options.integrator_fee,
+            payload_hash: transfer_options.payload_hash,
+        };
+        require!(
+            transfer_details.payload_hash.is_none() || transfer_details.partial_fill.is_none(),
+            "The payload isn't supported by the partially fillable transfers"
+        );
+        if let Some(native_fee_amount) = transfer_details.native_fee_amount {
+            require!(
+                transfer_details.partial_fill.is_none(),
+                "The native fee isn't supported by the partially fillable transfers"
+            );
+            require!(
+                native_fee_amount.0 > 0,
+                "The native fee amount should be a positive number"
+            );
+            self.lock_native_fee(&sender_id, native_fee_amount.0);
+        }
+
         let token_transfer_balance =
             self.get_user_balance(&sender_id, &transfer_message.transfer.token_near);
 
@@ -370,20 +708,38 @@ impl FastBridge {
             &transfer_message.transfer.amount.0,
         );
 
-        let token_fee_balance = self.get_user_balance(&sender_id, &transfer_message.fee.token);
+        // The fee can be paid in native NEAR only, then the sender may have no balance of the fee token
+        if transfer_message.fee.amount.0 > 0 {
+            let token_fee_balance = self.get_user_balance(&sender_id, &transfer_message.fee.token);
 
-        require!(
-            token_fee_balance >= transfer_message.fee.amount,
-            "Not enough fee token balance."
-        );
+            require!(
+                token_fee_balance >= transfer_message.fee.amount,
+                "Not enough fee token balance."
+            );
 
-        self.decrease_balance(
-            &sender_id,
-            &transfer_message.fee.token,
-            &transfer_message.fee.amount.0,
-        );
+            self.decrease_balance(
+                &sender_id,
+                &transfer_message.fee.token,
+                &transfer_message.fee.amount.0,
+            );
+        }
+
+        if let Some(integrator_fee) = transfer_details.integrator_fee.as_ref() {
+            require!(
+                transfer_details.partial_fill.is_none(),
+                "The integrator fee isn't supported by the partially fillable transfers"
+            );
+            self.lock_integrator_fee(&sender_id, &transfer_message, integrator_fee);
+        }
 
-        let nonce = U128::from(self.store_transfers(sender_id.clone(), transfer_message.clone()));
+        let nonce = U128::from(self.store_transfers(
+            sender_id.clone(),
+            transfer_message.clone(),
+            transfer_details.clone(),
+        ));
+        if let Some(quote_id) = transfer_options.quote_id {
+            self.bind_transfer_to_quote(nonce, quote_id, &transfer_message, last_block_height);
+        }
 
         if let Some(update_balance) = update_balance {
             Event::FastBridgeDepositEvent {
@@ -401,6 +757,12 @@ impl FastBridge {
         }
         .emit();
 
+        FastBridgeEvent::FastBridgeInitTransferDetailsEvent {
+            nonce,
+            transfer_details,
+        }
+        .emit();
+
         U128::from(0)
     }
 
@@ -426,12 +788,18 @@ impl FastBridge {
     ) -> Promise {
         let sender_id = env::predecessor_account_id();
         let is_only_originator_can_unlock = true;
+        let integrator_fee_refund = self.get_integrator_fee_refund(&nonce.0.to_string());
         self.unlock_internal(nonce, proof, is_only_originator_can_unlock)
             .then(
                 ext_self::ext(current_account_id())
                     .with_static_gas(utils::tera_gas(75))
                     .with_attached_deposit(utils::NO_DEPOSIT)
-                    .unlock_and_withdraw_callback(sender_id, recipient_id, None),
+                    .unlock_and_withdraw_callback(
+                        sender_id,
+                        recipient_id,
+                        None,
+                        integrator_fee_refund,
+                    ),
             )
     }
 
@@ -460,6 +828,7 @@ impl FastBridge {
     ) -> Promise {
         let sender_id = env::predecessor_account_id();
         let is_only_originator_can_unlock = true;
+        let integrator_fee_refund = self.get_integrator_fee_refund(&nonce.0.to_string());
         self.unlock_internal(nonce, proof, is_only_originator_can_unlock)
             .then(
                 ext_self::ext(current_account_id())
@@ -469,6 +838,7 @@ impl FastBridge {
                         sender_id,
                         Some(recipient_id),
                         Some(aurora_native_token_account_id),
+                        integrator_fee_refund,
                     ),
             )
     }
@@ -482,6 +852,7 @@ impl FastBridge {
         #[serializer(borsh)] sender_id: AccountId,
         #[serializer(borsh)] recipient_id: Option<AccountId>,
         #[serializer(borsh)] aurora_native_token_account_id: Option<AccountId>,
+        #[serializer(borsh)] integrator_fee_refund: U128,
     ) -> Promise {
         require!(
             transfer_message.fee.token == transfer_message.transfer.token_near,
@@ -493,17 +864,19 @@ impl FastBridge {
                 let aurora_sender = transfer_message
                     .aurora_sender
                     .unwrap_or_else(|| env::panic_str("Aurora sender can't be None"));
-                let aurora_sender_hex = hex::encode(aurora_sender.0);
-                if native_token == transfer_message.transfer.token_near {
-                    Some(format!("fake.near:0000000000000000000000000000000000000000000000000000000000000000{}", aurora_sender_hex))
-                } else {
-                    Some(aurora_sender_hex)
-                }
+                Some(aurora::get_aurora_deposit_msg(
+                    &transfer_message.transfer.token_near,
+                    &native_token,
+                    &aurora_sender,
+                ))
             }
             None => None,
         };
 
-        let amount = transfer_message.fee.amount.0 + transfer_message.transfer.amount.0;
+        // The integrator fee is refunded in the transfer token by `unlock_callback()`
+        let amount = transfer_message.fee.amount.0
+            + transfer_message.transfer.amount.0
+            + integrator_fee_refund.0;
         self.withdraw_internal(
             transfer_message.transfer.token_near,
             Some(amount.into()),
@@ -555,43 +928,56 @@ impl FastBridge {
             "Only the original creator of the transfer can perform the unlock"
         );
 
-        let storage_key_hash = utils::get_eth_storage_key_hash(
-            transfer_data.transfer.token_eth,
-            transfer_data.recipient,
-            eth_types::U256(nonce.0.into()),
-            eth_types::U256(transfer_data.transfer.amount.0.into()),
-        );
-
-        let expected_storage_value = vec![];
+        let amount_eth = self.get_pending_transfer_amount_eth(&nonce.0.to_string(), &transfer_data);
+        let partial_fill = self
+            .pending_transfers_details
+            .get(&nonce.0.to_string())
+            .and_then(|transfer_details| transfer_details.partial_fill);
+
+        // The transfer that allows partial fills is unlocked only if all its fills on Ethereum were claimed
+        let (storage_key_hash, expected_storage_value) = match partial_fill {
+            Some(partial_fill) => (
+                utils::get_eth_filled_amount_storage_key_hash(
+                    transfer_data.transfer.token_eth,
+                    transfer_data.recipient,
+                    eth_types::U256(nonce.0.into()),
+                    eth_types::U256(amount_eth.into()),
+                ),
+                utils::get_eth_storage_value(partial_fill.filled_amount_eth.0),
+            ),
+            None => (
+                utils::get_eth_storage_key_hash(
+                    transfer_data.transfer.token_eth,
+                    transfer_data.recipient,
+                    eth_types::U256(nonce.0.into()),
+                    eth_types::U256(amount_eth.into()),
+                    self.get_pending_transfer_payload_hash(&nonce.0.to_string()),
+                ),
+                vec![],
+            ),
+        };
 
-        ext_prover::ext(self.prover_account.clone())
-            .with_static_gas(utils::tera_gas(50))
-            .with_attached_deposit(utils::NO_DEPOSIT)
-            .verify_storage_proof(
-                proof.header_data,
-                proof.account_proof,
-                self.eth_bridge_contract.0.to_vec(),
-                proof.account_data,
-                storage_key_hash,
-                proof.storage_proof,
-                expected_storage_value,
-                transfer_data.valid_till_block_height,
-                None,
-                false,
-            )
-            .then(
-                ext_self::ext(current_account_id())
-                    .with_static_gas(utils::tera_gas(5))
-                    .with_attached_deposit(utils::NO_DEPOSIT)
-                    .unlock_callback(nonce),
-            )
+        self.verify_transfer_storage_proof(
+            &transfer_data.transfer.token_near,
+            proof,
+            storage_key_hash,
+            expected_storage_value,
+            transfer_data.valid_till_block_height,
+        )
+        .then(
+            ext_self::ext(current_account_id())
+                .with_static_gas(utils::tera_gas(5))
+                .with_attached_deposit(utils::NO_DEPOSIT)
+                .unlock_callback(nonce),
+        )
     }
 
     /// This function finalizes the execution flow of the `unlock()` function. This function
     /// is called as a callback from the `EthProver` contract after the `proof` of the non-existence
     /// of the transfer has been verified. It unlocks the transfer specified by the nonce, returns the appropriate
     /// amount of locked tokens to the transfer creator, and emits a `FastBridgeUnlockEvent`
-    /// with the details of the unlocked transfer.
+    /// with the details of the unlocked transfer. If the transfer was partially filled, only the unfilled
+    /// remainder is returned and the amounts of the returned `TransferMessage` are reduced accordingly.
     ///
     /// This function is only intended for internal use and should not be called directly by external accounts.
     ///
@@ -623,12 +1009,24 @@ impl FastBridge {
             block_timestamp() > transfer_data.valid_till,
             "Valid time is not correct."
         );
+        self.check_account_not_blocked(&recipient_id);
 
         require!(
             verification_result,
             format!("Verification failed for unlock proof")
         );
+        // The transfer isn't processed till its valid till block height, so the optimistic claim is false
+        self.slash_optimistic_claim(&nonce.0.to_string(), &recipient_id);
+
+        let mut transfer_data = transfer_data;
+        let (amount, fee_amount) =
+            self.get_pending_transfer_remainder(&nonce.0.to_string(), &transfer_data);
+        self.refund_native_fee(&nonce.0.to_string(), &recipient_id);
+        self.refund_integrator_fee(&nonce.0.to_string(), &transfer_data, &recipient_id);
+        self.remove_transfer(&nonce.0.to_string(), &transfer_data);
 
+        transfer_data.transfer.amount = U128(amount);
+        transfer_data.fee.amount = U128(fee_amount);
         self.increase_balance(
             &recipient_id,
             &transfer_data.transfer.token_near,
@@ -639,7 +1037,6 @@ impl FastBridge {
             &transfer_data.fee.token,
             &transfer_data.fee.amount.0,
         );
-        self.remove_transfer(&nonce.0.to_string(), &transfer_data);
 
         Event::FastBridgeUnlockEvent {
             nonce,
@@ -666,10 +1063,126 @@ impl FastBridge {
     /// # Panics
     ///
     /// The function will panic if the Ethereum Fast Bridge contract address in the provided proof does not
-    /// match the expected Fast Bridge contract's address stored in the contract state.
+    /// match the expected Fast Bridge contract's address stored in the contract state, or if the `unlock_recipient`
+    /// of the event is neither a valid NEAR account nor an Aurora address `aurora:0x{address}`. Such fills can be
+    /// claimed with `lp_unlock_by_relayer()`. The claims to Aurora addresses require the Aurora config.
     #[pause(except(roles(Role::UnrestrictedLpUnlock)))]
     pub fn lp_unlock(&mut self, proof: Proof) -> Promise {
         let parsed_proof = lp_relayer::EthTransferEvent::parse(proof.clone());
+        self.check_unlock_recipient(&parsed_proof.unlock_recipient);
+
+        self.lp_unlock_internal(proof, parsed_proof)
+    }
+
+    fn check_unlock_recipient(&self, unlock_recipient: &str) {
+        let unlock_recipient =
+            lp_relayer::UnlockRecipient::parse(unlock_recipient).unwrap_or_else(|| {
+                env::panic_str(&format!(
+                    "Invalid unlock recipient `{}`, the fill can be claimed only by the relayer",
+                    unlock_recipient
+                ))
+            });
+        if let lp_relayer::UnlockRecipient::Aurora(_) = unlock_recipient {
+            require!(
+                self.aurora_config.is_some(),
+                "The claims to Aurora are disabled"
+            );
+        }
+    }
+
+    /// Unlocks tokens that were transferred on the Ethereum, if the `unlock_recipient` of the transfer event
+    /// isn't a valid NEAR account. The relayer of the event claims the fill to the `recipient_id` by signing
+    /// the message "Claim the transfer {nonce} of the Fast Bridge `{fast bridge account}` to `{recipient_id}`"
+    /// with `personal_sign` of its Ethereum wallet.
+    ///
+    /// # Arguments
+    ///
+    /// * `proof` - A `Proof` for the event of the successful transfer on the Ethereum side.
+    /// * `recipient_id` - The account that receives the unlocked tokens.
+    /// * `signature` - The hex-encoded 65-byte signature of the claim message by the relayer.
+    #[pause(except(roles(Role::UnrestrictedLpUnlock)))]
+    pub fn lp_unlock_by_relayer(
+        &mut self,
+        proof: Proof,
+        recipient_id: AccountId,
+        signature: String,
+    ) -> Promise {
+        let mut parsed_proof = lp_relayer::EthTransferEvent::parse(proof.clone());
+        require!(
+            lp_relayer::UnlockRecipient::parse(&parsed_proof.unlock_recipient).is_none(),
+            "The unlock recipient is valid, the fill should be claimed with `lp_unlock`"
+        );
+
+        let message = eth_signature::get_relayer_claim_message(
+            &current_account_id(),
+            parsed_proof.nonce,
+            &recipient_id,
+        );
+        let signature = hex::decode(signature.trim_start_matches("0x"))
+            .unwrap_or_else(|_| env::panic_str("Invalid hex format of the signature"));
+        let signer = eth_signature::recover_eth_address(
+            &eth_signature::personal_message_hash(message.as_bytes()),
+            &signature,
+        );
+        require!(
+            signer == Some(parsed_proof.relayer),
+            "The claim isn't signed by the relayer"
+        );
+
+        parsed_proof.unlock_recipient = recipient_id.to_string();
+        self.lp_unlock_internal(proof, parsed_proof)
+    }
+
+    /// Unlocks tokens that were transferred on the Ethereum like `lp_unlock()` and then withdraws the amount
+    /// and the fee credited to the caller. If the withdrawal fails, the tokens are returned to the balance
+    /// of the caller in this contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `proof` - A `Proof` for the event of the successful transfer on the Ethereum side.
+    /// * `msg` - The message of `ft_transfer_call` for the withdrawal, `ft_transfer` is used if it's `None`.
+    ///
+    /// # Panics
+    ///
+    /// The function will panic if the caller isn't the `unlock_recipient` of the event.
+    #[pause(except(roles(Role::UnrestrictedLpUnlock)))]
+    pub fn lp_unlock_and_withdraw(&mut self, proof: Proof, msg: Option<String>) -> Promise {
+        let parsed_proof = lp_relayer::EthTransferEvent::parse(proof.clone());
+        let sender_id = get_caller_as_unlock_recipient(&parsed_proof);
+
+        self.lp_unlock_internal(proof, parsed_proof).then(
+            ext_self::ext(current_account_id())
+                .with_static_gas(utils::tera_gas(120))
+                .with_attached_deposit(utils::NO_DEPOSIT)
+                .lp_unlock_and_withdraw_callback(sender_id, msg),
+        )
+    }
+
+    #[private]
+    pub fn lp_unlock_and_withdraw_callback(
+        &mut self,
+        #[callback]
+        #[serializer(borsh)]
+        transfer_message: TransferMessage,
+        #[serializer(borsh)] sender_id: AccountId,
+        #[serializer(borsh)] msg: Option<String>,
+    ) -> Promise {
+        get_unlocked_token_amounts(&transfer_message)
+            .into_iter()
+            .map(|(token, amount)| {
+                self.withdraw_internal(
+                    token,
+                    Some(U128(amount)),
+                    sender_id.clone(),
+                    None,
+                    msg.clone(),
+                )
+            })
+            .reduce(|promise, withdraw_promise| promise.and(withdraw_promise))
+            .unwrap_or_else(|| env::panic_str("Nothing to withdraw"))
+    }
+
+    fn lp_unlock_internal(&self, proof: Proof, parsed_proof: EthTransferEvent) -> Promise {
         assert_eq!(
             parsed_proof.eth_bridge_contract,
             self.eth_bridge_contract,
@@ -678,21 +1191,26 @@ impl FastBridge {
             hex::encode(self.eth_bridge_contract.0),
         );
 
-        ext_prover::ext(self.prover_account.clone())
-            .with_static_gas(utils::tera_gas(50))
-            .with_attached_deposit(utils::NO_DEPOSIT)
-            .verify_log_entry(
-                proof.log_index,
-                proof.log_entry_data,
-                proof.receipt_index,
-                proof.receipt_data,
-                proof.header_data,
-                proof.proof,
-                false,
-            )
+        let is_aurora_recipient = matches!(
+            lp_relayer::UnlockRecipient::parse(&parsed_proof.unlock_recipient),
+            Some(lp_relayer::UnlockRecipient::Aurora(_))
+        );
+        // The claims to Aurora also withdraw the transfer and the fee tokens to the Aurora engine
+        let callback_gas = if is_aurora_recipient {
+            utils::tera_gas(170)
+        } else {
+            utils::tera_gas(50)
+        };
+
+        let (_, transfer_message) = self
+            .pending_transfers
+            .get(&parsed_proof.nonce.to_string())
+            .unwrap_or_else(|| env::panic_str("Transfer not found"));
+
+        self.verify_transfer_event_proof(&transfer_message.transfer.token_near, proof)
             .then(
                 ext_self::ext(current_account_id())
-                    .with_static_gas(utils::tera_gas(50))
+                    .with_static_gas(callback_gas)
                     .with_attached_deposit(utils::NO_DEPOSIT)
                     .verify_log_entry_callback(parsed_proof),
             )
@@ -730,22 +1248,29 @@ impl FastBridge {
             "Force unlock isn't allowed yet"
         );
 
-        self.increase_balance(
-            &recipient_id,
-            &transfer_data.transfer.token_near,
-            &transfer_data.transfer.amount.0,
-        );
-        self.increase_balance(
-            &recipient_id,
-            &transfer_data.fee.token,
-            &transfer_data.fee.amount.0,
-        );
+        self.check_account_not_blocked(&recipient_id);
+        // The manager settles the transfer, so the optimistic claim is released without slashing
+        self.release_optimistic_claim(&nonce_str);
+
+        let (amount, fee_amount) = self.get_pending_transfer_remainder(&nonce_str, &transfer_data);
+        self.increase_balance(&recipient_id, &transfer_data.transfer.token_near, &amount);
+        self.increase_balance(&recipient_id, &transfer_data.fee.token, &fee_amount);
+        self.refund_native_fee(&nonce_str, &recipient_id);
+        self.refund_integrator_fee(&nonce_str, &transfer_data, &recipient_id);
         self.remove_transfer(&nonce_str, &transfer_data);
 
+        // The legacy event is kept for the indexers that track the settled transfers by it
         Event::FastBridgeLpUnlockEvent {
+            nonce,
+            recipient_id: recipient_id.clone(),
+            transfer_message: transfer_data.clone(),
+        }
+        .emit();
+        FastBridgeEvent::FastBridgeForceUnlockEvent {
             nonce,
             recipient_id,
             transfer_message: transfer_data,
+            unlocked_by: env::predecessor_account_id(),
         }
         .emit();
     }
@@ -756,6 +1281,10 @@ impl FastBridge {
     /// If the verification is successful, the function checks if the transfer is valid and if so, executes
     /// the transfer on NEAR by increasing the balance of the recipient's account.
     /// It also emits a `FastBridgeLpUnlockEvent` event to signal that a transfer was successfully executed.
+    /// A partial fill credits the pro-rata part of the transfer and keeps the remainder pending.
+    /// If the LP registry mode is enabled, the fee is paid only to the registered LPs.
+    /// The fee of the transfer with the fee schedule decays by the block of the fill, the rest is refunded.
+    /// The tokens claimed to an Aurora address are withdrawn to the Aurora engine with `ft_transfer_call`.
     ///
     /// This function is only intended for internal use and should not be called directly by external accounts.
     ///
@@ -764,27 +1293,35 @@ impl FastBridge {
     /// * `verification_success`: a boolean value indicating whether the verification of the event log entry was successful.
     /// * `proof`: an `EthTransferEvent` object containing the data of the transfer.
     ///
+    /// # Returns
+    ///
+    /// The transfer message with the amount and the fee amount credited to the unlock recipient.
+    ///
     /// # Panics
     ///
     /// This function will panic if it cannot find a pending transfer with the given nonce or if any of the checks
     /// on the transfer data fail.
     #[private]
+    #[result_serializer(borsh)]
     pub fn verify_log_entry_callback(
         &mut self,
         #[callback]
         #[serializer(borsh)]
         verification_success: bool,
         #[serializer(borsh)] proof: EthTransferEvent,
-    ) {
+    ) -> TransferMessage {
         require!(verification_success, "Failed to verify the proof");
+        self.lp_unlock_verified(proof)
+    }
 
+    /// Credits the LP for the verified transfer event, see `verify_log_entry_callback()`.
+    fn lp_unlock_verified(&mut self, proof: EthTransferEvent) -> TransferMessage {
         let nonce_str = proof.nonce.to_string();
 
-        let transfer = self
+        let (originator_id, transfer_data) = self
             .pending_transfers
             .get(&nonce_str)
             .unwrap_or_else(|| panic!("Transaction with id: {} not found", &nonce_str));
-        let transfer_data = transfer.1;
 
         require!(
             proof.recipient == transfer_data.recipient,
@@ -802,33 +1339,103 @@ impl FastBridge {
             )
         );
 
+        let unlock_recipient = lp_relayer::UnlockRecipient::parse(&proof.unlock_recipient)
+            .unwrap_or_else(|| env::panic_str("Invalid unlock recipient"));
+        let recipient_id = unlock_recipient.account_id();
+        self.check_account_not_blocked(&recipient_id);
+        self.use_transfer_reservation(&nonce_str, &proof.relayer, proof.eth_block_height);
+        self.settle_optimistic_claim(&nonce_str, &proof, &recipient_id);
+
+        if let Some(filled_amount) = proof.filled_amount {
+            let (amount, fee_amount) = self.unlock_partial_fill(
+                &nonce_str,
+                &originator_id,
+                &transfer_data,
+                &proof,
+                filled_amount,
+                recipient_id.clone(),
+            );
+            self.withdraw_lp_unlock_to_aurora(&unlock_recipient, &recipient_id, &transfer_data);
+
+            let mut unlocked_transfer = transfer_data;
+            unlocked_transfer.transfer.amount = U128(amount);
+            unlocked_transfer.fee.amount = U128(fee_amount);
+            return unlocked_transfer;
+        }
+
+        let amount_eth = self.get_pending_transfer_amount_eth(&nonce_str, &transfer_data);
         require!(
-            proof.amount == transfer_data.transfer.amount.0,
+            proof.amount == amount_eth,
             format!(
                 "Wrong amount transferred {}, expected {}",
-                proof.amount, transfer_data.transfer.amount.0
+                proof.amount, amount_eth
             )
         );
+        let is_partially_filled = self
+            .pending_transfers_details
+            .get(&nonce_str)
+            .and_then(|transfer_details| transfer_details.partial_fill)
+            .map_or(false, |partial_fill| partial_fill.filled_amount_eth.0 > 0);
+        require!(
+            !is_partially_filled,
+            "The transfer is already partially filled"
+        );
+
+        // The LP is reimbursed only for the fill that delivered the exact payload of the transfer
+        let payload_hash = self.get_pending_transfer_payload_hash(&nonce_str);
+        require!(
+            proof.payload_hash == payload_hash,
+            "Wrong payload hash of the transfer"
+        );
+        if payload_hash.is_some() {
+            let expected_transfer_id = utils::get_transfer_id(
+                transfer_data.transfer.token_eth,
+                transfer_data.recipient,
+                eth_types::U256(proof.nonce.into()),
+                eth_types::U256(amount_eth.into()),
+                payload_hash,
+            );
+            require!(
+                proof.transfer_id.0 .0.as_slice() == expected_transfer_id.as_slice(),
+                "Wrong transfer id of the transfer with the payload"
+            );
+        }
 
-        let recipient_id = proof.unlock_recipient.parse().unwrap();
         self.increase_balance(
             &recipient_id,
             &transfer_data.transfer.token_near,
             &transfer_data.transfer.amount.0,
         );
-        self.increase_balance(
+        let fee_amount = self.apply_fee_schedule(
+            &nonce_str,
+            &originator_id,
+            &transfer_data,
+            &proof,
+            transfer_data.fee.amount.0,
+        );
+        let fee_amount = self.credit_lp_fee(
+            U128(proof.nonce),
             &recipient_id,
+            &originator_id,
+            &proof.relayer,
             &transfer_data.fee.token,
-            &transfer_data.fee.amount.0,
+            fee_amount,
         );
+        self.pay_native_fee(&nonce_str, &recipient_id, &originator_id, &proof.relayer);
+        self.pay_integrator_fee(&nonce_str, &transfer_data);
         self.remove_transfer(&nonce_str, &transfer_data);
+        self.withdraw_lp_unlock_to_aurora(&unlock_recipient, &recipient_id, &transfer_data);
 
         Event::FastBridgeLpUnlockEvent {
             nonce: U128(proof.nonce),
             recipient_id,
-            transfer_message: transfer_data,
+            transfer_message: transfer_data.clone(),
         }
         .emit();
+
+        let mut unlocked_transfer = transfer_data;
+        unlocked_transfer.fee.amount = U128(fee_amount);
+        unlocked_transfer
     }
 
     /// Gets the user balance of the specified token in this contract. These tokens can be immediately withdrawn.
@@ -896,9 +1503,18 @@ impl FastBridge {
 
         self.check_whitelist_token_and_account(&transfer_message.transfer.token_near, sender_id);
         self.check_whitelist_token_and_account(&transfer_message.fee.token, sender_id);
+        self.check_eth_address_not_denied(&transfer_message.recipient);
+        self.check_eth_address_not_denied(&transfer_message.transfer.token_eth);
+        self.check_token_pair(&transfer_message.transfer);
     }
 
-    fn store_transfers(&mut self, sender_id: AccountId, transfer_message: TransferMessage) -> u128 {
+    fn store_transfers(
+        &mut self,
+        sender_id: AccountId,
+        transfer_message: TransferMessage,
+        transfer_details: TransferDetails,
+    ) -> u128 {
+        let initial_storage_usage = env::storage_usage();
         let new_balance = self
             .pending_transfers_balances
             .get(&transfer_message.transfer.token_near)
@@ -909,23 +1525,75 @@ impl FastBridge {
             .insert(&transfer_message.transfer.token_near, &new_balance);
 
         self.nonce += 1;
-        let account_pending = (sender_id, transfer_message);
+        let transfer_id = self.nonce.to_string();
+        let account_pending = (sender_id.clone(), transfer_message);
         self.pending_transfers
-            .insert(&self.nonce.to_string(), &account_pending);
+            .insert(&transfer_id, &account_pending);
+        self.pending_transfers_details
+            .insert(&transfer_id, &transfer_details);
+        self.lock_transfer_storage_bond(&sender_id, &transfer_id, initial_storage_usage);
         self.nonce
     }
 
     fn remove_transfer(&mut self, transfer_id: &String, transfer_message: &TransferMessage) {
+        let (amount, _) = self.get_pending_transfer_remainder(transfer_id, transfer_message);
         let new_balance = self
             .pending_transfers_balances
             .get(&transfer_message.transfer.token_near)
             .unwrap_or_else(|| env::panic_str("Pending balance does not exist"))
-            - transfer_message.transfer.amount.0;
+            - amount;
 
         self.pending_transfers_balances
             .insert(&transfer_message.transfer.token_near, &new_balance);
 
-        self.pending_transfers.remove(transfer_id);
+        self.pending_transfers_details.remove(transfer_id);
+        if let Some((originator_id, _)) = self.pending_transfers.remove(transfer_id) {
+            self.refund_transfer_storage_bond(&originator_id, transfer_id);
+            // The reservation that is still present wasn't used by the LP to fill the transfer
+            self.release_transfer_reservation(transfer_id, originator_id, true);
+        }
+    }
+
+    /// Gets the amount of the Ethereum token of the pending transfer. The transfers that were created
+    /// before the details were introduced have the same amount on both sides.
+    fn get_pending_transfer_amount_eth(
+        &self,
+        transfer_id: &String,
+        transfer_message: &TransferMessage,
+    ) -> u128 {
+        self.pending_transfers_details
+            .get(transfer_id)
+            .map(|transfer_details| transfer_details.amount_eth.0)
+            .unwrap_or(transfer_message.transfer.amount.0)
+    }
+
+    /// Gets the hash of the payload of the pending transfer, `None` if the transfer has no payload.
+    fn get_pending_transfer_payload_hash(&self, transfer_id: &String) -> Option<[u8; 32]> {
+        self.pending_transfers_details
+            .get(transfer_id)
+            .and_then(|transfer_details| transfer_details.payload_hash)
+    }
+
+    /// Gets the transfer amount and the fee amount of the pending transfer that weren't credited to the LPs yet.
+    fn get_pending_transfer_remainder(
+        &self,
+        transfer_id: &String,
+        transfer_message: &TransferMessage,
+    ) -> (u128, u128) {
+        match self
+            .pending_transfers_details
+            .get(transfer_id)
+            .and_then(|transfer_details| transfer_details.partial_fill)
+        {
+            Some(partial_fill) => (
+                transfer_message.transfer.amount.0 - partial_fill.unlocked_amount.0,
+                transfer_message.fee.amount.0 - partial_fill.unlocked_fee_amount.0,
+            ),
+            None => (
+                transfer_message.transfer.amount.0,
+                transfer_message.fee.amount.0,
+            ),
+        }
     }
 
     /// Withdraws the specified `amount` of tokens from the provided token account ID from the balance of the caller.
@@ -970,11 +1638,13 @@ impl FastBridge {
     ) -> Promise {
         let user_balance = self.get_user_balance(&sender_id, &token_id);
         let amount = amount.unwrap_or(user_balance);
+        let recipient_id = recipient_id.unwrap_or_else(|| sender_id.clone());
+        self.check_account_not_blocked(&sender_id);
+        self.check_account_not_blocked(&recipient_id);
 
         require!(amount.0 > 0, "The amount should be a positive number");
         require!(amount <= user_balance, "Insufficient user balance");
         self.decrease_balance(&sender_id, &token_id, &amount.0);
-        let recipient_id = recipient_id.unwrap_or_else(|| sender_id.clone());
 
         if let Some(msg) = msg {
             self.call_ft_transfer_call(token_id, amount, sender_id, recipient_id, msg)
@@ -1080,7 +1750,9 @@ impl FastBridge {
     /// * `prover_account`: An `AccountId` representing the `EthProver` account to use.
     #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
     pub fn set_prover_account(&mut self, prover_account: AccountId) {
-        self.prover_account = prover_account;
+        self.prover_account = prover_account.clone();
+
+        FastBridgeEvent::FastBridgeSetProverAccountEvent { prover_account }.emit();
     }
 
     /// Sets the eth client account. `EthClient` is a contract that provide the last block number.
@@ -1089,7 +1761,12 @@ impl FastBridge {
     /// * `account_id`: An `AccountId` representing the `EthClient` account to use.
     #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
     pub fn set_eth_client_account(&mut self, account_id: AccountId) {
-        self.eth_client_account = account_id;
+        self.eth_client_account = account_id.clone();
+
+        FastBridgeEvent::FastBridgeSetEthClientAccountEvent {
+            eth_client_account: account_id,
+        }
+        .emit();
     }
 
     /// Sets the Ethereum Fast Bridge contract address.
@@ -1103,6 +1780,11 @@ impl FastBridge {
     #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
     pub fn set_eth_bridge_contract_address(&mut self, address: String) {
         self.eth_bridge_contract = fast_bridge_common::get_eth_address(address);
+
+        FastBridgeEvent::FastBridgeSetEthBridgeContractEvent {
+            eth_bridge_contract: self.eth_bridge_contract,
+        }
+        .emit();
     }
 
     /// Gets the minimum and maximum possible time for the tokens lock period.
@@ -1166,6 +1848,16 @@ impl FastBridge {
         self.pending_transfers.get(&id)
     }
 
+    /// Gets the details of the pending transfer that aren't a part of the `TransferMessage`,
+    /// e.g. the amount of the Ethereum token that should be transferred to the recipient.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string representing the transfer ID (nonce).
+    pub fn get_pending_transfer_details(&self, id: String) -> Option<TransferDetails> {
+        self.pending_transfers_details.get(&id)
+    }
+
     /// Sets the lock time for the contract.
     ///
     /// The function is allowed to be called only by accounts that have `ConfigManager` role.
@@ -1201,6 +1893,18 @@ impl FastBridge {
             lock_time_min,
             lock_time_max,
         };
+
+        FastBridgeEvent::FastBridgeSetLockTimeEvent {
+            lock_time_min,
+            lock_time_max,
+        }
+        .emit();
+    }
+
+    /// Gets the version of the schema of the events emitted by the contract, so indexers are able to
+    /// adapt to the changes of the events payload.
+    pub fn get_event_schema_version(&self) -> String {
+        events::EVENT_SCHEMA_VERSION.to_string()
     }
 }
 
@@ -1305,6 +2009,9 @@ mod unit_tests {
         };
     }
 
+    /// The Ethereum address of the private key `1`, the relayer of the LPs in the tests.
+    const LP_ETH_RELAYER: &str = "7e5f4552091a69125d5dfcb7b8c2659029395bdf";
+
     /// Generate a valid ethereum address.
     fn ethereum_address_from_id(id: u8) -> String {
         let mut buffer = vec![id];
@@ -1423,16 +2130,44 @@ mod unit_tests {
             .to_string()
     }
 
-    #[test]
-    fn test_ft_on_transfer_with_message() {
-        let context = get_context(false);
-        testing_env!(context);
-        let mut contract = get_bridge_contract(None);
+    fn last_event() -> serde_json::Value {
+        serde_json::from_str(
+            near_sdk::test_utils::get_logs()
+                .last()
+                .unwrap()
+                .strip_prefix("EVENT_JSON:")
+                .unwrap(),
+        )
+        .unwrap()
+    }
 
-        let transfer_account: AccountId = AccountId::try_from("bob_near".to_string()).unwrap();
-        let balance = U128(100);
-        let current_timestamp = block_timestamp() + contract.lock_duration.lock_time_min + 1;
-        let msg = json!({
+    fn deposit_transfer_storage(contract: &mut FastBridge, account_id: &AccountId) {
+        contract.increase_transfer_storage_balance(account_id, near_sdk::ONE_NEAR);
+    }
+
+    /// Initializes the transfer of the `token_pair_transfer_message()` from the signer with the `transfer_options`.
+    fn init_transfer_with_options(contract: &mut FastBridge, transfer_options: TransferOptions) {
+        contract.ft_on_transfer(signer_account_id(), U128(200), "".to_string());
+        deposit_transfer_storage(contract, &signer_account_id());
+        contract.init_transfer_callback(
+            10,
+            token_pair_transfer_message(contract, eth_token_address()),
+            signer_account_id(),
+            None,
+            transfer_options,
+        );
+    }
+
+    #[test]
+    fn test_ft_on_transfer_with_message() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+
+        let transfer_account: AccountId = AccountId::try_from("bob_near".to_string()).unwrap();
+        let balance = U128(100);
+        let current_timestamp = block_timestamp() + contract.lock_duration.lock_time_min + 1;
+        let msg = json!({
             "valid_till": current_timestamp,
             "transfer": {
                 "token_near": "token_near",
@@ -1687,11 +2422,13 @@ mod unit_tests {
              "recipient": eth_recipient_address()
         });
 
+        deposit_transfer_storage(&mut contract, &signer_account_id());
         contract.init_transfer_callback(
             10,
             serde_json::from_value(msg).unwrap(),
             signer_account_id(),
             None,
+            TransferOptions::default(),
         );
 
         let transfer_token_amount = contract
@@ -1733,11 +2470,13 @@ mod unit_tests {
              "recipient": eth_recipient_address()
         });
 
+        deposit_transfer_storage(&mut contract, &signer_account_id());
         contract.init_transfer_callback(
             10,
             serde_json::from_value(msg).unwrap(),
             signer_account_id(),
             None,
+            TransferOptions::default(),
         );
 
         assert_eq!(
@@ -1779,6 +2518,11 @@ mod unit_tests {
             contract.get_user_balance(&recipient_id, &transfer_token),
             balance
         );
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("fast_bridge_force_unlock_event")
+                && log.contains("\"version\":\"1.21.0\"")
+                && log.contains("\"unlocked_by\":\"unlocker\"")));
     }
 
     #[test]
@@ -1905,6 +2649,7 @@ mod unit_tests {
             serde_json::from_value(msg).unwrap(),
             signer_account_id(),
             None,
+            TransferOptions::default(),
         );
     }
 
@@ -1949,6 +2694,7 @@ mod unit_tests {
             serde_json::from_value(msg).unwrap(),
             signer_account_id(),
             None,
+            TransferOptions::default(),
         );
     }
 
@@ -1983,11 +2729,13 @@ mod unit_tests {
             },
              "recipient": eth_recipient_address()
         });
+        deposit_transfer_storage(&mut contract, &signer_account_id());
         contract.init_transfer_callback(
             10,
             serde_json::from_value(msg).unwrap(),
             signer_account_id(),
             None,
+            TransferOptions::default(),
         );
 
         let transfer_token_amount = contract
@@ -2038,11 +2786,13 @@ mod unit_tests {
             },
              "recipient": eth_recipient_address()
         });
+        deposit_transfer_storage(&mut contract, &signer_account_id());
         contract.init_transfer_callback(
             10,
             serde_json::from_value(msg).unwrap(),
             signer_account_id(),
             None,
+            TransferOptions::default(),
         );
 
         let transfer_token_amount = contract
@@ -2088,6 +2838,7 @@ mod unit_tests {
             serde_json::from_value(msg).unwrap(),
             signer_account_id(),
             None,
+            TransferOptions::default(),
         );
     }
 
@@ -2121,6 +2872,7 @@ mod unit_tests {
             serde_json::from_value(msg).unwrap(),
             signer_account_id(),
             None,
+            TransferOptions::default(),
         );
     }
 
@@ -2159,6 +2911,7 @@ mod unit_tests {
             serde_json::from_value(msg).unwrap(),
             signer_account_id(),
             None,
+            TransferOptions::default(),
         );
     }
 
@@ -2195,11 +2948,13 @@ mod unit_tests {
             },
              "recipient": eth_recipient_address()
         });
+        deposit_transfer_storage(&mut contract, &signer_account_id());
         contract.init_transfer_callback(
             10,
             serde_json::from_value(msg).unwrap(),
             signer_account_id(),
             None,
+            TransferOptions::default(),
         );
 
         let transfer_token_amount = contract
@@ -2268,11 +3023,13 @@ mod unit_tests {
             },
              "recipient": eth_recipient_address()
         });
+        deposit_transfer_storage(contract, &signer_account_id());
         contract.init_transfer_callback(
             10,
             serde_json::from_value(msg).unwrap(),
             signer_account_id(),
             None,
+            TransferOptions::default(),
         );
 
         let transfer_token_amount = contract
@@ -2389,6 +3146,28 @@ mod unit_tests {
         assert_eq!(contract.lock_duration.lock_time_max / convert_nano, 420);
     }
 
+    #[test]
+    fn test_set_lock_time_event() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_lock_time("1h".to_string(), "2h".to_string());
+
+        assert_eq!(
+            last_event(),
+            json!({
+                "standard": "fast-bridge",
+                "version": contract.get_event_schema_version(),
+                "event": "fast_bridge_set_lock_time_event",
+                "data": {
+                    "lock_time_min": 3_600_000_000_000u64,
+                    "lock_time_max": 7_200_000_000_000u64,
+                }
+            })
+        );
+    }
+
     #[test]
     fn test_update_balance() {
         let context = get_context(false);
@@ -2554,4 +3333,2119 @@ mod unit_tests {
             }
         }
     }
+
+    #[test]
+    fn test_whitelist_change_events() {
+        set_env!(predecessor_account_id: accounts(0));
+        let mut contract = get_bridge_contract(None);
+        let token_account = accounts(1);
+        let sender_account = accounts(2);
+
+        contract.set_token_whitelist_mode(token_account.clone(), WhitelistMode::CheckToken);
+        contract
+            .set_token_whitelist_mode(token_account.clone(), WhitelistMode::CheckAccountAndToken);
+        assert_eq!(
+            last_event()["data"],
+            json!({
+                "token": token_account,
+                "previous_mode": "CheckToken",
+                "mode": "CheckAccountAndToken",
+                "changed_by": accounts(0),
+            })
+        );
+
+        contract
+            .add_token_to_account_whitelist(Some(token_account.clone()), sender_account.clone());
+        assert_eq!(
+            last_event()["data"],
+            json!({
+                "token": token_account,
+                "account": sender_account,
+                "was_whitelisted": false,
+                "changed_by": accounts(0),
+            })
+        );
+
+        contract.remove_token_from_account_whitelist(Some(token_account.clone()), sender_account);
+        assert_eq!(last_event()["data"]["was_whitelisted"], json!(true));
+
+        contract.set_whitelist_mode_enabled(false);
+        assert_eq!(
+            last_event()["data"],
+            json!({
+                "previous_enabled": true,
+                "enabled": false,
+                "changed_by": accounts(0),
+            })
+        );
+    }
+
+    #[test]
+    fn test_batch_account_whitelist() {
+        set_env!(predecessor_account_id: accounts(0));
+        let mut contract = get_bridge_contract(None);
+        let tokens: [AccountId; 2] = [
+            "token1.near".parse().unwrap(),
+            "token2.near".parse().unwrap(),
+        ];
+        let account: AccountId = "account1.near".parse().unwrap();
+
+        contract.set_token_whitelist_modes(
+            tokens
+                .iter()
+                .map(|token| (token.clone(), WhitelistMode::CheckAccountAndToken))
+                .collect(),
+        );
+
+        let entries = vec![
+            whitelist::AccountWhitelistEntry {
+                token: Some(tokens[0].clone()),
+                account: account.clone(),
+            },
+            whitelist::AccountWhitelistEntry {
+                token: Some(tokens[1].clone()),
+                account: account.clone(),
+            },
+            whitelist::AccountWhitelistEntry {
+                token: Some(tokens[1].clone()),
+                account: account.clone(),
+            },
+            whitelist::AccountWhitelistEntry {
+                token: Some("token3.near".parse().unwrap()),
+                account: account.clone(),
+            },
+            whitelist::AccountWhitelistEntry {
+                token: None,
+                account: account.clone(),
+            },
+        ];
+        assert_eq!(
+            contract.add_tokens_to_accounts_whitelist(entries),
+            vec![
+                whitelist::WhitelistEntryStatus::Added,
+                whitelist::WhitelistEntryStatus::Added,
+                whitelist::WhitelistEntryStatus::AlreadyWhitelisted,
+                whitelist::WhitelistEntryStatus::TokenModeNotSet,
+                whitelist::WhitelistEntryStatus::Added,
+            ]
+        );
+
+        let mut cleared_tokens = contract.clear_account_whitelist(account.clone());
+        cleared_tokens.sort();
+        assert_eq!(cleared_tokens, tokens.to_vec());
+        assert_eq!(contract.get_whitelist_accounts(), vec![account.to_string()]);
+        assert!(contract
+            .get_whitelisted_tokens_for_account(account.clone(), 0, 10)
+            .is_empty());
+
+        assert_eq!(
+            contract.remove_tokens_from_accounts_whitelist(vec![
+                whitelist::AccountWhitelistEntry {
+                    token: None,
+                    account: account.clone(),
+                },
+                whitelist::AccountWhitelistEntry {
+                    token: Some(tokens[0].clone()),
+                    account,
+                },
+            ]),
+            vec![
+                whitelist::WhitelistEntryStatus::Removed,
+                whitelist::WhitelistEntryStatus::NotWhitelisted,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_whitelist_views() {
+        set_env!(predecessor_account_id: accounts(0));
+        let mut contract = get_bridge_contract(None);
+        let token: AccountId = "token1.near".parse().unwrap();
+        let token_account: AccountId = "account1.near".parse().unwrap();
+        let global_account: AccountId = "account2.near".parse().unwrap();
+
+        contract.set_token_whitelist_mode(token.clone(), WhitelistMode::CheckAccountAndToken);
+        contract.add_token_to_account_whitelist(Some(token.clone()), token_account.clone());
+        contract.add_token_to_account_whitelist(None, global_account.clone());
+
+        assert_eq!(
+            contract.is_account_whitelisted(token.clone(), token_account.clone()),
+            Some(whitelist::AccountWhitelistRule::TokenAccount)
+        );
+        assert_eq!(
+            contract.is_account_whitelisted(token.clone(), global_account.clone()),
+            Some(whitelist::AccountWhitelistRule::Account)
+        );
+        assert_eq!(
+            contract.is_account_whitelisted(token.clone(), accounts(3)),
+            None
+        );
+        assert_eq!(
+            contract.get_whitelisted_tokens_for_account(token_account.clone(), 0, 10),
+            vec![token.clone()]
+        );
+        assert!(contract
+            .get_whitelisted_tokens_for_account(global_account, 0, 10)
+            .is_empty());
+
+        let second_token: AccountId = "token2.near".parse().unwrap();
+        contract
+            .set_token_whitelist_mode(second_token.clone(), WhitelistMode::CheckAccountAndToken);
+        contract.add_token_to_account_whitelist(Some(second_token.clone()), token_account.clone());
+        assert_eq!(
+            contract.get_whitelisted_tokens_for_account(token_account.clone(), 1, 10),
+            vec![second_token]
+        );
+        assert!(contract
+            .get_whitelisted_tokens_for_account(token_account, 2, 10)
+            .is_empty());
+
+        let tokens_count = contract.get_whitelist_tokens().len();
+        assert_eq!(
+            contract.get_whitelist_tokens_paginated(tokens_count - 2, 10),
+            contract.get_whitelist_tokens()[tokens_count - 2..]
+        );
+        assert_eq!(
+            contract.get_whitelist_accounts_paginated(1, 1),
+            contract.get_whitelist_accounts()[1..2]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The account `charlie` is blocked")]
+    fn test_blocked_account_deposit() {
+        set_env!(predecessor_account_id: accounts(0));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("BlocklistManager".to_string(), accounts(0));
+        contract.set_whitelist_mode_enabled(false);
+
+        let sender_account = accounts(2);
+        assert!(contract.add_account_to_blocklist(sender_account.clone()));
+        assert!(contract.is_account_blocked(sender_account.clone()));
+        assert_eq!(
+            contract.get_blocked_accounts(0, 10),
+            vec![sender_account.clone()]
+        );
+
+        set_env!(predecessor_account_id: accounts(1), signer_account_id: sender_account.clone());
+        contract.ft_on_transfer(sender_account, U128(1_000_000), "".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "The account `charlie` is blocked")]
+    fn test_withdraw_to_blocked_account() {
+        set_env!(predecessor_account_id: accounts(0));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("BlocklistManager".to_string(), accounts(0));
+        contract.add_account_to_blocklist(accounts(2));
+
+        let token_account: AccountId = "token_near".parse().unwrap();
+        set_env!(predecessor_account_id: token_account.clone());
+        contract.ft_on_transfer(accounts(1), U128(100), "".to_string());
+
+        set_env!(predecessor_account_id: accounts(1));
+        contract.withdraw(token_account, None, Some(accounts(2)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The account `bob_near` is blocked")]
+    fn test_unlock_to_blocked_account() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(&mut contract, TransferOptions::default());
+        contract.acl_grant_role(
+            "BlocklistManager".to_string(),
+            "token_near".parse().unwrap(),
+        );
+        contract.add_account_to_blocklist(signer_account_id());
+
+        testing_env!(get_context_for_unlock(false));
+        contract.unlock_callback(true, U128(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "is denied")]
+    fn test_denied_eth_recipient() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+
+        assert_eq!(
+            contract.add_eth_addresses_to_denylist(vec![
+                eth_recipient_address(),
+                eth_recipient_address()
+            ]),
+            vec![true, false]
+        );
+        assert!(contract.is_eth_address_denied(eth_recipient_address()));
+        assert_eq!(
+            contract.get_eth_address_denylist(0, 10),
+            vec![get_eth_address(eth_recipient_address())]
+        );
+
+        let current_timestamp = block_timestamp() + contract.lock_duration.lock_time_min + 1;
+        let msg = json!({
+            "valid_till": current_timestamp,
+            "transfer": {
+                "token_near": "token_near",
+                "token_eth": eth_token_address(),
+                "amount": "100"
+            },
+            "fee": {
+                "token": "token_near",
+                "amount": "100"
+            },
+             "recipient": eth_recipient_address()
+        });
+        contract
+            .validate_transfer_message(&serde_json::from_value(msg).unwrap(), &signer_account_id());
+    }
+
+    fn token_pair_transfer_message(contract: &FastBridge, token_eth: String) -> TransferMessage {
+        let current_timestamp = block_timestamp() + contract.lock_duration.lock_time_min + 1;
+        serde_json::from_value(json!({
+            "valid_till": current_timestamp,
+            "transfer": {
+                "token_near": "token_near",
+                "token_eth": token_eth,
+                "amount": "100"
+            },
+            "fee": {
+                "token": "token_near",
+                "amount": "100"
+            },
+             "recipient": eth_recipient_address()
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_registered_token_pair() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_token_pair_check_enabled(true);
+        contract.set_token_pair("token_near".parse().unwrap(), eth_token_address(), 18, 18);
+
+        assert_eq!(
+            contract.get_token_pair("token_near".parse().unwrap()),
+            Some(token_pairs::TokenPair {
+                token_eth: get_eth_address(eth_token_address()),
+                near_decimals: 18,
+                eth_decimals: 18,
+            })
+        );
+        contract.validate_transfer_message(
+            &token_pair_transfer_message(&contract, eth_token_address()),
+            &signer_account_id(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "can't be transferred to the Ethereum token")]
+    fn test_token_pair_mismatch() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_token_pair("token_near".parse().unwrap(), eth_token_address(), 18, 18);
+
+        contract.validate_transfer_message(
+            &token_pair_transfer_message(&contract, LP_ETH_RELAYER.to_string()),
+            &signer_account_id(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The token pair for `token_near` is not registered")]
+    fn test_token_pair_not_registered() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_token_pair_check_enabled(true);
+
+        contract.validate_transfer_message(
+            &token_pair_transfer_message(&contract, eth_token_address()),
+            &signer_account_id(),
+        );
+    }
+
+    fn eth_transfer_event(nonce: u128, amount: u128, unlock_recipient: &str) -> EthTransferEvent {
+        EthTransferEvent {
+            eth_bridge_contract: get_eth_address(eth_bridge_address()),
+            nonce,
+            relayer: get_eth_address(LP_ETH_RELAYER.to_string()),
+            token: get_eth_address(eth_token_address()),
+            recipient: get_eth_address(eth_recipient_address()),
+            amount,
+            filled_amount: None,
+            unlock_recipient: unlock_recipient.to_string(),
+            transfer_id: vec![0u8; 32].try_into().unwrap(),
+            payload_hash: None,
+            eth_block_height: None,
+        }
+    }
+
+    #[test]
+    fn test_lp_unlock_with_converted_decimals() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_token_pair("token_near".parse().unwrap(), eth_token_address(), 24, 18);
+
+        let amount = 3 * 10u128.pow(6);
+        contract.ft_on_transfer(signer_account_id(), U128(2 * amount), "".to_string());
+        deposit_transfer_storage(&mut contract, &signer_account_id());
+
+        let mut transfer_message = token_pair_transfer_message(&contract, eth_token_address());
+        transfer_message.transfer.amount = U128(amount);
+        transfer_message.fee.amount = U128(amount);
+        contract.init_transfer_callback(
+            10,
+            transfer_message,
+            signer_account_id(),
+            None,
+            TransferOptions::default(),
+        );
+
+        assert_eq!(
+            contract.get_pending_transfer_details("1".to_string()),
+            Some(TransferDetails {
+                amount_eth: U128(3),
+                partial_fill: None,
+                fee_schedule: None,
+                native_fee_amount: None,
+                integrator_fee: None,
+                payload_hash: None,
+            })
+        );
+
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 3, relayer.as_str()));
+        assert_eq!(
+            contract.get_user_balance(&relayer, &"token_near".parse().unwrap()),
+            U128(2 * amount)
+        );
+        assert_eq!(contract.get_pending_transfer_details("1".to_string()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't be converted to 18 decimals of the Ethereum token")]
+    fn test_transfer_amount_with_decimals_remainder() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_token_pair("token_near".parse().unwrap(), eth_token_address(), 24, 18);
+
+        let mut transfer_message = token_pair_transfer_message(&contract, eth_token_address());
+        transfer_message.transfer.amount = U128(10u128.pow(6) + 1);
+        contract.get_transfer_amount_eth(&transfer_message.transfer);
+    }
+
+    fn init_transfer_with_storage_bond(contract: &mut FastBridge) {
+        contract.ft_on_transfer(signer_account_id(), U128(200), "".to_string());
+
+        let current_timestamp = block_timestamp() + contract.lock_duration.lock_time_min + 1;
+        let msg = json!({
+            "valid_till": current_timestamp,
+            "transfer": {
+                "token_near": "token_near",
+                "token_eth": eth_token_address(),
+                "amount": "100"
+            },
+            "fee": {
+                "token": "token_near",
+                "amount": "100"
+            },
+             "recipient": eth_recipient_address()
+        });
+        contract.init_transfer_callback(
+            10,
+            serde_json::from_value(msg).unwrap(),
+            signer_account_id(),
+            None,
+            TransferOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_transfer_storage_deposit() {
+        set_env!(predecessor_account_id: accounts(0));
+        let mut contract = get_bridge_contract(None);
+
+        set_env!(predecessor_account_id: accounts(1), attached_deposit: near_sdk::ONE_NEAR);
+        contract.transfer_storage_deposit(None);
+        contract.transfer_storage_deposit(Some(accounts(2)));
+
+        assert_eq!(
+            contract.get_transfer_storage_balance(&accounts(1)),
+            U128(near_sdk::ONE_NEAR)
+        );
+        assert_eq!(
+            contract.get_transfer_storage_balance(&accounts(2)),
+            U128(near_sdk::ONE_NEAR)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough storage balance to store the transfer")]
+    fn test_init_transfer_without_storage_balance() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+
+        init_transfer_with_storage_bond(&mut contract);
+    }
+
+    #[test]
+    fn test_transfer_storage_bond_refunded_on_unlock() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        let transfer_account = signer_account_id();
+
+        deposit_transfer_storage(&mut contract, &transfer_account);
+        init_transfer_with_storage_bond(&mut contract);
+
+        let nonce = U128(1);
+        let bond = contract.get_transfer_storage_bond(nonce).0;
+        assert!(bond > 0);
+        assert_eq!(
+            contract.get_transfer_storage_balance(&transfer_account).0,
+            near_sdk::ONE_NEAR - bond
+        );
+
+        let context = get_context_for_unlock(false);
+        testing_env!(context);
+        contract.unlock_callback(true, nonce);
+
+        assert_eq!(contract.get_transfer_storage_bond(nonce), U128(0));
+        assert_eq!(
+            contract.get_transfer_storage_balance(&transfer_account),
+            U128(near_sdk::ONE_NEAR)
+        );
+    }
+
+    fn partial_fill_event(
+        amount: u128,
+        filled_amount: u128,
+        unlock_recipient: &str,
+    ) -> EthTransferEvent {
+        EthTransferEvent {
+            filled_amount: Some(filled_amount),
+            transfer_id: utils::get_transfer_id(
+                get_eth_address(eth_token_address()),
+                get_eth_address(eth_recipient_address()),
+                eth_types::U256(1u128.into()),
+                eth_types::U256(100u128.into()),
+                None,
+            )
+            .try_into()
+            .unwrap(),
+            ..eth_transfer_event(1, amount, unlock_recipient)
+        }
+    }
+
+    #[test]
+    fn test_lp_partial_fills() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                allow_partial_fills: true,
+                ..Default::default()
+            },
+        );
+        let token: AccountId = "token_near".parse().unwrap();
+
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        contract.verify_log_entry_callback(true, partial_fill_event(30, 30, relayer.as_str()));
+        assert_eq!(contract.get_user_balance(&relayer, &token), U128(60));
+        assert_eq!(contract.get_pending_balance(token.clone()), 70);
+        assert_eq!(
+            contract
+                .get_pending_transfer_details("1".to_string())
+                .unwrap()
+                .partial_fill,
+            Some(PartialFill {
+                filled_amount_eth: U128(30),
+                unlocked_amount: U128(30),
+                unlocked_fee_amount: U128(30),
+                claimed_fills: vec![U128(30)],
+            })
+        );
+
+        let another_relayer: AccountId = "another_relayer.near".parse().unwrap();
+        contract
+            .verify_log_entry_callback(true, partial_fill_event(70, 100, another_relayer.as_str()));
+        assert_eq!(
+            contract.get_user_balance(&another_relayer, &token),
+            U128(140)
+        );
+        assert_eq!(contract.get_pending_balance(token), 0);
+        assert!(contract.get_pending_transfer("1".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "The partial fill is already claimed")]
+    fn test_lp_partial_fill_claimed_twice() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                allow_partial_fills: true,
+                ..Default::default()
+            },
+        );
+
+        contract.verify_log_entry_callback(true, partial_fill_event(30, 30, "relayer.near"));
+        contract.verify_log_entry_callback(true, partial_fill_event(30, 30, "relayer.near"));
+    }
+
+    #[test]
+    #[should_panic(expected = "The partial fill should be at least 10")]
+    fn test_lp_partial_fill_below_min_fill_amount() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                allow_partial_fills: true,
+                ..Default::default()
+            },
+        );
+
+        contract.verify_log_entry_callback(true, partial_fill_event(9, 9, "relayer.near"));
+    }
+
+    #[test]
+    #[should_panic(expected = "The transfer doesn't allow partial fills")]
+    fn test_lp_partial_fill_not_allowed() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(&mut contract, TransferOptions::default());
+
+        contract.verify_log_entry_callback(true, partial_fill_event(30, 30, "relayer.near"));
+    }
+
+    #[test]
+    fn test_unlock_partially_filled_transfer() {
+        let context = get_context(false);
+        testing_env!(context);
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                allow_partial_fills: true,
+                ..Default::default()
+            },
+        );
+        let token: AccountId = "token_near".parse().unwrap();
+
+        contract.verify_log_entry_callback(true, partial_fill_event(25, 25, "relayer.near"));
+
+        let context = get_context_for_unlock(false);
+        testing_env!(context);
+        let transfer_message = contract.unlock_callback(true, U128(1));
+
+        assert_eq!(transfer_message.transfer.amount, U128(75));
+        assert_eq!(transfer_message.fee.amount, U128(75));
+        assert_eq!(
+            contract.get_user_balance(&signer_account_id(), &token),
+            U128(150)
+        );
+        assert_eq!(contract.get_pending_balance(token), 0);
+    }
+
+    fn reserve_transfer_for_relayer(contract: &mut FastBridge, eth_relayer: String) {
+        init_transfer_with_options(contract, TransferOptions::default());
+
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_transfer_reservation_config(Some(reservations::ReservationConfig {
+            min_bond: U128(near_sdk::ONE_NEAR),
+            duration_blocks: 10,
+        }));
+
+        set_env!(
+            predecessor_account_id: accounts(3),
+            attached_deposit: near_sdk::ONE_NEAR
+        );
+        contract.reserve_transfer(U128(1), eth_relayer);
+        testing_env!(get_context(false));
+    }
+
+    #[test]
+    #[should_panic(expected = "The reservation should be backed by a bond")]
+    fn test_reserve_transfer_with_zero_bond() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(&mut contract, TransferOptions::default());
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_transfer_reservation_config(Some(reservations::ReservationConfig {
+            min_bond: U128(1),
+            duration_blocks: 10,
+        }));
+
+        set_env!(predecessor_account_id: accounts(3), attached_deposit: 0);
+        contract.reserve_transfer(U128(1), LP_ETH_RELAYER.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "The minimal reservation bond should be positive")]
+    fn test_set_transfer_reservation_config_with_zero_bond() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_transfer_reservation_config(Some(reservations::ReservationConfig {
+            min_bond: U128(0),
+            duration_blocks: 10,
+        }));
+    }
+
+    #[test]
+    fn test_reserved_transfer_filled_by_reserving_relayer() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        reserve_transfer_for_relayer(&mut contract, LP_ETH_RELAYER.to_string());
+        assert!(contract.get_transfer_reservation(U128(1)).is_some());
+
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 100, relayer.as_str()));
+
+        assert!(contract.get_transfer_reservation(U128(1)).is_none());
+        assert_eq!(
+            contract.get_user_balance(&relayer, &"token_near".parse().unwrap()),
+            U128(200)
+        );
+        assert!(near_sdk::test_utils::get_logs().iter().any(|log| log
+            .contains("fast_bridge_release_transfer_reservation_event")
+            && log.contains("\"is_slashed\":false")));
+    }
+
+    #[test]
+    #[should_panic(expected = "The transfer is reserved by the relayer")]
+    fn test_reserved_transfer_filled_by_another_relayer() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        reserve_transfer_for_relayer(&mut contract, ethereum_address_from_id(2));
+
+        // The window of 10 blocks starts at the estimated Ethereum block height 10
+        contract.verify_log_entry_callback(
+            true,
+            EthTransferEvent {
+                eth_block_height: Some(19),
+                ..eth_transfer_event(1, 100, "relayer.near")
+            },
+        );
+    }
+
+    #[test]
+    fn test_reserved_transfer_filled_by_another_relayer_after_window() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        reserve_transfer_for_relayer(&mut contract, ethereum_address_from_id(2));
+        assert_eq!(
+            contract
+                .get_transfer_reservation(U128(1))
+                .unwrap()
+                .valid_till_block_height,
+            20
+        );
+
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        contract.verify_log_entry_callback(
+            true,
+            EthTransferEvent {
+                eth_block_height: Some(20),
+                ..eth_transfer_event(1, 100, relayer.as_str())
+            },
+        );
+
+        assert!(contract.get_transfer_reservation(U128(1)).is_none());
+        assert_eq!(
+            contract.get_user_balance(&relayer, &"token_near".parse().unwrap()),
+            U128(200)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The block height of the fill of the reserved transfer is unknown")]
+    fn test_reserved_transfer_filled_by_another_relayer_at_unknown_height() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        reserve_transfer_for_relayer(&mut contract, ethereum_address_from_id(2));
+
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 100, "relayer.near"));
+    }
+
+    #[test]
+    #[should_panic(expected = "The transfer is already reserved")]
+    fn test_reserve_reserved_transfer() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        reserve_transfer_for_relayer(&mut contract, LP_ETH_RELAYER.to_string());
+
+        set_env!(
+            predecessor_account_id: accounts(4),
+            attached_deposit: near_sdk::ONE_NEAR
+        );
+        contract.reserve_transfer(U128(1), ethereum_address_from_id(2));
+    }
+
+    #[test]
+    fn test_reservation_slashed_on_unlock() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        reserve_transfer_for_relayer(&mut contract, LP_ETH_RELAYER.to_string());
+
+        testing_env!(get_context_for_unlock(false));
+        contract.unlock_callback(true, U128(1));
+
+        assert!(contract.get_transfer_reservation(U128(1)).is_none());
+        assert!(near_sdk::test_utils::get_logs().iter().any(|log| log
+            .contains("fast_bridge_release_transfer_reservation_event")
+            && log.contains("\"is_slashed\":true")));
+    }
+
+    /// The declaration of the `LP_ETH_RELAYER` by the `lp_account`, signed with the nonce 0 for `alice_near`.
+    fn lp_eth_relayer(lp_account: &AccountId) -> lp_registry::LpEthRelayer {
+        let signature = match lp_account.as_str() {
+            "danny" => "7d2305484b71a074bb9f15ce24e84a77c212105444d605de20b7853e41608e0b76e7383a7c103419547272c588492e87e38af2359cbb2b9960a427e39faed8e71b",
+            "eugene" => "d266e753b14ead23a6b67b9230888318f2604b97c90826247d178e55c47ed0474c4dd12975365e2fcefcd0665b5196b63b5371588f937c79f520c794f5c27f2d1c",
+            _ => panic!("No relayer signature for the LP `{}`", lp_account),
+        };
+        lp_registry::LpEthRelayer {
+            eth_relayer: LP_ETH_RELAYER.to_string(),
+            signature: signature.to_string(),
+        }
+    }
+
+    fn register_lp_with_relayer(contract: &mut FastBridge, lp_account: AccountId) {
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_lp_stake_token("token_near".parse().unwrap(), Some(U128(50)));
+        contract.ft_on_transfer(lp_account.clone(), U128(100), "".to_string());
+
+        set_env!(predecessor_account_id: lp_account);
+        contract.register_lp(
+            "token_near".parse().unwrap(),
+            U128(100),
+            vec![lp_eth_relayer(&lp_account)],
+        );
+        testing_env!(get_context(false));
+    }
+
+    fn init_transfer_for_lp_registry(contract: &mut FastBridge) {
+        init_transfer_with_options(contract, TransferOptions::default());
+        contract.set_lp_registry_enabled(true);
+    }
+
+    #[test]
+    fn test_register_lp() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(3));
+
+        assert_eq!(
+            contract.get_registered_lp(accounts(3)),
+            Some(lp_registry::RegisteredLp {
+                stake_token: "token_near".parse().unwrap(),
+                stake_amount: U128(100),
+                eth_relayers: vec![get_eth_address(LP_ETH_RELAYER.to_string())],
+            })
+        );
+        assert_eq!(
+            contract.get_lp_by_eth_relayer(LP_ETH_RELAYER.to_string()),
+            Some(accounts(3))
+        );
+        assert_eq!(
+            contract.get_eth_relayer_nonce(LP_ETH_RELAYER.to_string()),
+            1
+        );
+        assert_eq!(
+            contract.get_user_balance(&accounts(3), &"token_near".parse().unwrap()),
+            U128(0)
+        );
+
+        set_env!(predecessor_account_id: accounts(3), block_timestamp: 1u64);
+        let unbonding = contract.unregister_lp();
+        assert_eq!(unbonding.stake_amount, U128(100));
+        assert_eq!(
+            unbonding.unbonded_at,
+            1 + contract.lock_duration.lock_time_max
+        );
+        assert_eq!(
+            contract.get_lp_by_eth_relayer(LP_ETH_RELAYER.to_string()),
+            None
+        );
+        assert_eq!(
+            contract.get_user_balance(&accounts(3), &"token_near".parse().unwrap()),
+            U128(0)
+        );
+
+        set_env!(
+            predecessor_account_id: accounts(3),
+            block_timestamp: unbonding.unbonded_at
+        );
+        assert_eq!(contract.withdraw_lp_stake(), U128(100));
+        assert!(contract.get_lp_unbonding(accounts(3)).is_none());
+        assert_eq!(
+            contract.get_user_balance(&accounts(3), &"token_near".parse().unwrap()),
+            U128(100)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "didn't sign the declaration")]
+    fn test_register_lp_with_relayer_of_wrong_signer() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_lp_stake_token("token_near".parse().unwrap(), Some(U128(50)));
+        contract.ft_on_transfer(accounts(3), U128(100), "".to_string());
+
+        set_env!(predecessor_account_id: accounts(3));
+        contract.register_lp(
+            "token_near".parse().unwrap(),
+            U128(100),
+            vec![lp_registry::LpEthRelayer {
+                eth_relayer: LP_ETH_RELAYER.to_string(),
+                // Signed by the private key `2` over the valid declaration message
+                signature: "37648bbb44fdf9383fe245f78b8c77984c39927cde643714ed2f053e5699c76159392a0b11165e1dd4d9647d3f15eee901d568b981b2f501aed6ee08a4587f101c".to_string(),
+            }],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "didn't sign the declaration")]
+    fn test_register_lp_with_replayed_relayer_signature() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(3));
+
+        set_env!(predecessor_account_id: accounts(3), block_timestamp: 1u64);
+        let unbonding = contract.unregister_lp();
+        set_env!(
+            predecessor_account_id: accounts(3),
+            block_timestamp: unbonding.unbonded_at
+        );
+        contract.withdraw_lp_stake();
+
+        testing_env!(get_context(false));
+        register_lp_with_relayer(&mut contract, accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "The LP stake is still unbonding")]
+    fn test_withdraw_unbonding_lp_stake() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(3));
+
+        set_env!(predecessor_account_id: accounts(3), block_timestamp: 1u64);
+        let unbonding = contract.unregister_lp();
+
+        set_env!(
+            predecessor_account_id: accounts(3),
+            block_timestamp: unbonding.unbonded_at - 1
+        );
+        contract.withdraw_lp_stake();
+    }
+
+    #[test]
+    fn test_lp_registry_fee_paid_to_registered_lp() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(3));
+        init_transfer_for_lp_registry(&mut contract);
+
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 100, accounts(3).as_str()));
+
+        assert_eq!(
+            contract.get_user_balance(&accounts(3), &"token_near".parse().unwrap()),
+            U128(200)
+        );
+    }
+
+    #[test]
+    fn test_lp_registry_fee_refunded_for_unregistered_lp() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        init_transfer_for_lp_registry(&mut contract);
+
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 100, accounts(3).as_str()));
+
+        let token: AccountId = "token_near".parse().unwrap();
+        assert_eq!(contract.get_user_balance(&accounts(3), &token), U128(100));
+        assert_eq!(
+            contract.get_user_balance(&signer_account_id(), &token),
+            U128(100)
+        );
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("fast_bridge_lp_fee_refund_event")));
+    }
+
+    #[test]
+    fn test_lp_unlock_to_aurora_address() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_aurora_config(Some(aurora::AuroraConfig {
+            engine_account: "aurora".parse().unwrap(),
+            native_token_account: "eth.bridge.near".parse().unwrap(),
+        }));
+        init_transfer_with_options(&mut contract, TransferOptions::default());
+
+        let aurora_address = ethereum_address_from_id(5);
+        contract.verify_log_entry_callback(
+            true,
+            eth_transfer_event(1, 100, &format!("aurora:0x{}", aurora_address)),
+        );
+
+        let recipient_id: AccountId = format!("0x{}", aurora_address).parse().unwrap();
+        assert_eq!(
+            contract.get_user_balance(&recipient_id, &"token_near".parse().unwrap()),
+            U128(0)
+        );
+        assert!(contract.get_pending_transfer("1".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "The claims to Aurora are disabled")]
+    fn test_lp_unlock_to_aurora_address_disabled() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        init_transfer_for_lp_registry(&mut contract);
+
+        contract.verify_log_entry_callback(
+            true,
+            eth_transfer_event(1, 100, &format!("aurora:0x{}", ethereum_address_from_id(5))),
+        );
+    }
+
+    #[test]
+    fn test_lp_unlock_and_withdraw_callback() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(3));
+        init_transfer_for_lp_registry(&mut contract);
+        contract.ft_on_transfer(accounts(3), U128(50), "".to_string());
+
+        let transfer_message = contract
+            .verify_log_entry_callback(true, eth_transfer_event(1, 100, accounts(3).as_str()));
+        assert_eq!(transfer_message.transfer.amount, U128(100));
+        assert_eq!(transfer_message.fee.amount, U128(100));
+
+        contract.lp_unlock_and_withdraw_callback(transfer_message, accounts(3), None);
+        assert_eq!(
+            contract.get_user_balance(&accounts(3), &"token_near".parse().unwrap()),
+            U128(50)
+        );
+    }
+
+    #[test]
+    fn test_lp_unlock_and_withdraw_refunded_fee() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        init_transfer_for_lp_registry(&mut contract);
+
+        let transfer_message = contract
+            .verify_log_entry_callback(true, eth_transfer_event(1, 100, accounts(3).as_str()));
+        assert_eq!(transfer_message.fee.amount, U128(0));
+
+        contract.lp_unlock_and_withdraw_callback(transfer_message, accounts(3), None);
+        assert_eq!(
+            contract.get_user_balance(&accounts(3), &"token_near".parse().unwrap()),
+            U128(0)
+        );
+    }
+
+    #[test]
+    fn test_lp_unlock_and_bridge_back_callback() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(3));
+        init_transfer_for_lp_registry(&mut contract);
+
+        let transfer_message = contract
+            .verify_log_entry_callback(true, eth_transfer_event(1, 100, accounts(3).as_str()));
+        contract.lp_unlock_and_bridge_back_callback(
+            transfer_message,
+            accounts(3),
+            get_eth_address(eth_recipient_address()),
+        );
+
+        let token: AccountId = "token_near".parse().unwrap();
+        assert_eq!(contract.get_user_balance(&accounts(3), &token), U128(0));
+
+        testing_env!(
+            get_context(false),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Failed]
+        );
+        let bridged_amount = contract.bridge_back_callback(
+            token.clone(),
+            U128(200),
+            accounts(3),
+            get_eth_address(eth_recipient_address()),
+        );
+        assert_eq!(bridged_amount, U128(0));
+        assert_eq!(contract.get_user_balance(&accounts(3), &token), U128(200));
+    }
+
+    #[test]
+    fn test_set_proof_verifier() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        let token: AccountId = "token_near".parse().unwrap();
+        let zk_verifier = proof_verifier::ProofVerifier::ZkLightClient {
+            verifier_account: "zk_verifier.near".parse().unwrap(),
+        };
+
+        contract.set_proof_verifier(None, Some(proof_verifier::ProofVerifier::Committee));
+        contract.set_proof_verifier(Some(token.clone()), Some(zk_verifier.clone()));
+        assert_eq!(
+            contract.get_proof_verifier(None),
+            proof_verifier::ProofVerifier::Committee
+        );
+        assert_eq!(
+            contract.get_proof_verifier(Some(token.clone())),
+            zk_verifier
+        );
+        assert_eq!(
+            contract.get_token_proof_verifiers(0, 10),
+            vec![(token.clone(), zk_verifier)]
+        );
+
+        contract.set_proof_verifier(Some(token.clone()), None);
+        contract.set_proof_verifier(None, None);
+        assert_eq!(
+            contract.get_proof_verifier(Some(token)),
+            proof_verifier::ProofVerifier::RainbowProver
+        );
+        assert!(contract.get_token_proof_verifiers(0, 10).is_empty());
+    }
+
+    #[test]
+    fn test_committee_proof_verifier_uses_prover() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_proof_verifier(None, Some(proof_verifier::ProofVerifier::Committee));
+
+        // The proofs of the tokens verified by the committee are still accepted without the attestations
+        drop(contract.verify_transfer_event_proof(
+            &"token_near".parse().unwrap(),
+            Proof {
+                log_index: 0,
+                log_entry_data: vec![],
+                receipt_index: 0,
+                receipt_data: vec![],
+                header_data: vec![],
+                proof: vec![],
+            },
+        ));
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        assert_eq!(receipts.last().unwrap().receiver_id, prover());
+    }
+
+    fn attested_transfer_event() -> committee::AttestedTransferEvent {
+        committee::AttestedTransferEvent {
+            nonce: U128(1),
+            relayer: "1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string(),
+            token: eth_token_address(),
+            recipient: eth_recipient_address(),
+            amount: U128(100),
+            filled_amount: None,
+            unlock_recipient: accounts(3).to_string(),
+            transfer_id: hex::encode([0u8; 32]),
+            payload_hash: None,
+        }
+    }
+
+    fn init_transfer_for_committee(contract: &mut FastBridge) {
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_proof_verifier(None, Some(proof_verifier::ProofVerifier::Committee));
+        contract.set_committee(
+            vec![
+                "7e5f4552091a69125d5dfcb7b8c2659029395bdf".to_string(),
+                "2b5ad5c4795c026514f8317c7a215e218dccd6cf".to_string(),
+                "6813eb9362372eef6200f3b1dbc3f819671cba69".to_string(),
+            ],
+            2,
+        );
+        init_transfer_with_options(contract, TransferOptions::default());
+    }
+
+    #[test]
+    fn test_lp_unlock_with_attestations() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_for_committee(&mut contract);
+
+        contract.lp_unlock_with_attestations(
+            attested_transfer_event(),
+            vec![
+                "9e73dbcf3770e7bf59e3d221e1ad63dbd7e86b6ce59cdd710eaead49065f18b10242af3e93080634f37e210010b646bf077b8d383902f0b93f8a96ac784000911c".to_string(),
+                "7ffb39faf97330941d39e1994c1c08c773dd9f2f73f43f9feb17c2b3bb33604919bed4e023cda6a16ea2e0ece34a8f606de079ec59ca3658b2794e8833c4b5491c".to_string(),
+            ],
+        );
+
+        assert_eq!(
+            contract.get_user_balance(&accounts(3), &"token_near".parse().unwrap()),
+            U128(200)
+        );
+        assert!(contract.get_pending_transfer("1".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough committee attestations 1, expected 2")]
+    fn test_lp_unlock_with_duplicated_attestations() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_for_committee(&mut contract);
+
+        let signature = "fe963dcad828615a98ef77c0f4d6b5b265f0e66706a369ebd46d3e8f7f5bd88b01a61927377bf7a62cce7d5f446d734c24ad1cd08263e05d14e3f60709ce0e3d1b".to_string();
+        contract.lp_unlock_with_attestations(
+            attested_transfer_event(),
+            vec![signature.clone(), signature],
+        );
+    }
+
+    fn claim_transfer_optimistically(
+        contract: &mut FastBridge,
+    ) -> optimistic_claims::OptimisticClaim {
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_optimistic_claim_config(Some(optimistic_claims::OptimisticClaimConfig {
+            min_bond: U128(near_sdk::ONE_NEAR),
+            bond_bps: 1000,
+            challenge_period: 100,
+        }));
+        contract.ft_on_transfer(accounts(4), U128(10), "".to_string());
+        init_transfer_with_options(contract, TransferOptions::default());
+
+        set_env!(
+            predecessor_account_id: accounts(4),
+            attached_deposit: near_sdk::ONE_NEAR
+        );
+        // The fill block can't be ahead of the Ethereum block height 10 estimated at the claim
+        contract.lp_claim_optimistic(U128(1), LP_ETH_RELAYER.to_string(), accounts(3), 10)
+    }
+
+    #[test]
+    fn test_optimistic_claim_finalized() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        let claim = claim_transfer_optimistically(&mut contract);
+        assert_eq!(contract.get_optimistic_claim(U128(1)), Some(claim.clone()));
+
+        assert_eq!(claim.token_bond, U128(10));
+        assert_eq!(
+            contract.get_user_balance(&accounts(4), &"token_near".parse().unwrap()),
+            U128(0)
+        );
+
+        set_env!(block_timestamp: claim.challenge_till);
+        contract.finalize_optimistic_claim(U128(1));
+
+        let token: AccountId = "token_near".parse().unwrap();
+        assert_eq!(contract.get_user_balance(&accounts(3), &token), U128(200));
+        assert_eq!(contract.get_user_balance(&accounts(4), &token), U128(10));
+        assert!(contract.get_optimistic_claim(U128(1)).is_none());
+        assert!(contract.get_pending_transfer("1".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "The fill block height is ahead of the Ethereum chain")]
+    fn test_optimistic_claim_ahead_of_chain() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        claim_transfer_optimistically(&mut contract);
+
+        set_env!(
+            predecessor_account_id: accounts(4),
+            attached_deposit: near_sdk::ONE_NEAR
+        );
+        contract.lp_claim_optimistic(U128(1), LP_ETH_RELAYER.to_string(), accounts(3), 11);
+    }
+
+    #[test]
+    fn test_optimistic_claim_slashed_by_another_fill() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        claim_transfer_optimistically(&mut contract);
+
+        // The proven fill of the claimed relayer is made to another recipient
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        testing_env!(get_context(false));
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 100, relayer.as_str()));
+
+        let token: AccountId = "token_near".parse().unwrap();
+        assert_eq!(contract.get_user_balance(&relayer, &token), U128(210));
+        assert_eq!(contract.get_user_balance(&accounts(4), &token), U128(0));
+        assert!(contract.get_optimistic_claim(U128(1)).is_none());
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("fast_bridge_challenge_optimistic_claim_event")));
+    }
+
+    #[test]
+    #[should_panic(expected = "The challenge window isn't over yet")]
+    fn test_optimistic_claim_finalized_too_early() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        claim_transfer_optimistically(&mut contract);
+
+        contract.finalize_optimistic_claim(U128(1));
+    }
+
+    #[test]
+    fn test_optimistic_claim_challenged() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        let claim = claim_transfer_optimistically(&mut contract);
+
+        testing_env!(get_context(false));
+        contract.challenge_optimistic_claim_callback(true, U128(1), claim, accounts(5));
+
+        assert!(contract.get_optimistic_claim(U128(1)).is_none());
+        assert!(contract.get_pending_transfer("1".to_string()).is_some());
+        let token: AccountId = "token_near".parse().unwrap();
+        assert_eq!(contract.get_user_balance(&accounts(3), &token), U128(0));
+        assert_eq!(contract.get_user_balance(&accounts(5), &token), U128(10));
+    }
+
+    #[test]
+    fn test_optimistic_claim_of_reserved_transfer_challenged() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_transfer_reservation_config(Some(reservations::ReservationConfig {
+            min_bond: U128(near_sdk::ONE_NEAR),
+            duration_blocks: 10,
+        }));
+        contract.set_optimistic_claim_config(Some(optimistic_claims::OptimisticClaimConfig {
+            min_bond: U128(near_sdk::ONE_NEAR),
+            bond_bps: 1000,
+            challenge_period: 100,
+        }));
+        contract.ft_on_transfer(accounts(4), U128(10), "".to_string());
+        init_transfer_with_options(&mut contract, TransferOptions::default());
+
+        set_env!(
+            predecessor_account_id: accounts(4),
+            attached_deposit: near_sdk::ONE_NEAR
+        );
+        contract.reserve_transfer(U128(1), LP_ETH_RELAYER.to_string());
+        let claim =
+            contract.lp_claim_optimistic(U128(1), LP_ETH_RELAYER.to_string(), accounts(3), 10);
+        // The bond of the reservation stays locked until the claim is settled
+        assert!(contract.get_transfer_reservation(U128(1)).is_some());
+
+        testing_env!(get_context(false));
+        contract.challenge_optimistic_claim_callback(true, U128(1), claim, accounts(5));
+
+        assert!(contract.get_transfer_reservation(U128(1)).is_none());
+        assert!(near_sdk::test_utils::get_logs().iter().any(|log| log
+            .contains("fast_bridge_release_transfer_reservation_event")
+            && log.contains(&format!("\"recipient_id\":\"{}\"", signer_account_id()))
+            && log.contains("\"is_slashed\":true")));
+    }
+
+    #[test]
+    fn test_unlock_optimistically_claimed_transfer() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        claim_transfer_optimistically(&mut contract);
+
+        // The proof of the unlock shows that the claimed fill never happened
+        testing_env!(get_context_for_unlock(false));
+        contract.unlock_callback(true, U128(1));
+
+        assert!(contract.get_optimistic_claim(U128(1)).is_none());
+        assert_eq!(
+            contract.get_user_balance(&signer_account_id(), &"token_near".parse().unwrap()),
+            U128(210)
+        );
+    }
+
+    #[test]
+    fn test_lp_unlock_with_fee_schedule() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                end_fee_amount: Some(U128(20)),
+                ..Default::default()
+            },
+        );
+
+        // The fill in the middle of the 300 blocks of the lock period gets the middle of the fee range
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        contract.verify_log_entry_callback(
+            true,
+            EthTransferEvent {
+                eth_block_height: Some(160),
+                ..eth_transfer_event(1, 100, relayer.as_str())
+            },
+        );
+
+        let token: AccountId = "token_near".parse().unwrap();
+        assert_eq!(contract.get_user_balance(&relayer, &token), U128(160));
+        assert_eq!(
+            contract.get_user_balance(&signer_account_id(), &token),
+            U128(40)
+        );
+    }
+
+    #[test]
+    fn test_lp_unlock_with_fee_schedule_without_fill_height() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                end_fee_amount: Some(U128(20)),
+                ..Default::default()
+            },
+        );
+
+        // The fill without the proven block height is settled at the end fee amount
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 100, relayer.as_str()));
+
+        let token: AccountId = "token_near".parse().unwrap();
+        assert_eq!(contract.get_user_balance(&relayer, &token), U128(120));
+        assert_eq!(
+            contract.get_user_balance(&signer_account_id(), &token),
+            U128(80)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The end fee amount can't exceed the fee amount")]
+    fn test_fee_schedule_with_end_fee_above_fee() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                end_fee_amount: Some(U128(101)),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn publish_quote_as(
+        contract: &mut FastBridge,
+        lp_account: AccountId,
+        max_amount: u128,
+        fee_bps: u32,
+    ) -> U128 {
+        deposit_transfer_storage(contract, &lp_account);
+        set_env!(predecessor_account_id: lp_account);
+        let quote_id = contract.publish_quote(
+            "token_near".parse().unwrap(),
+            LP_ETH_RELAYER.to_string(),
+            U128(max_amount),
+            U128(max_amount),
+            fee_bps,
+            1000,
+            100,
+        );
+        testing_env!(get_context(false));
+        quote_id
+    }
+
+    #[test]
+    fn test_best_quote() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(4));
+
+        let quote_id = publish_quote_as(&mut contract, accounts(4), 100, 50);
+        publish_quote_as(&mut contract, accounts(4), 50, 30);
+        publish_quote_as(&mut contract, accounts(4), 100, 70);
+
+        let (best_quote_id, best_quote) = contract
+            .get_best_quote("token_near".parse().unwrap(), U128(100), 0, 10)
+            .unwrap();
+        assert_eq!(best_quote_id, quote_id);
+        assert_eq!(best_quote.fee_bps, 50);
+        assert_eq!(contract.get_quotes(0, 10).len(), 3);
+
+        set_env!(block_timestamp: 1000u64);
+        assert!(contract
+            .get_best_quote("token_near".parse().unwrap(), U128(100), 0, 10)
+            .is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "The relayer isn't declared by the registered LP")]
+    fn test_publish_quote_without_registration() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        publish_quote_as(&mut contract, accounts(4), 100, 50);
+    }
+
+    #[test]
+    fn test_best_quote_of_lp_below_min_stake() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(4));
+        publish_quote_as(&mut contract, accounts(4), 100, 50);
+
+        contract.set_lp_stake_token("token_near".parse().unwrap(), Some(U128(200)));
+        assert!(contract
+            .get_best_quote("token_near".parse().unwrap(), U128(100), 0, 10)
+            .is_none());
+    }
+
+    #[test]
+    fn test_unregister_lp_removes_quotes() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(4));
+        let quote_id = publish_quote_as(&mut contract, accounts(4), 100, 50);
+        assert!(contract.get_quote(quote_id).unwrap().storage_bond.0 > 0);
+        assert!(contract.get_transfer_storage_balance(&accounts(4)).0 < near_sdk::ONE_NEAR);
+
+        set_env!(predecessor_account_id: accounts(4));
+        contract.unregister_lp();
+
+        assert!(contract.get_quote(quote_id).is_none());
+        assert!(contract
+            .get_best_quote("token_near".parse().unwrap(), U128(100), 0, 10)
+            .is_none());
+        assert_eq!(
+            contract.get_transfer_storage_balance(&accounts(4)),
+            U128(near_sdk::ONE_NEAR)
+        );
+    }
+
+    #[test]
+    fn test_publish_quote_prunes_expired_quotes() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(4));
+        let expired_quote_id = publish_quote_as(&mut contract, accounts(4), 100, 50);
+
+        set_env!(predecessor_account_id: accounts(4), block_timestamp: 1000u64);
+        let quote_id = contract.publish_quote(
+            "token_near".parse().unwrap(),
+            LP_ETH_RELAYER.to_string(),
+            U128(100),
+            U128(100),
+            70,
+            2000,
+            100,
+        );
+
+        assert!(contract.get_quote(expired_quote_id).is_none());
+        assert_eq!(
+            contract.get_quotes(0, 10),
+            vec![(quote_id, contract.get_quote(quote_id).unwrap())]
+        );
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("fast_bridge_remove_quote_event")));
+    }
+
+    #[test]
+    fn test_init_transfer_bound_to_quote() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(4));
+        let quote_id = publish_quote_as(&mut contract, accounts(4), 100, 1000);
+
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                quote_id: Some(quote_id),
+                ..Default::default()
+            },
+        );
+
+        let reservation = contract.get_transfer_reservation(U128(1)).unwrap();
+        assert_eq!(reservation.lp_account, accounts(4));
+        assert_eq!(
+            reservation.eth_relayer,
+            get_eth_address(LP_ETH_RELAYER.to_string())
+        );
+        assert_eq!(reservation.bond, U128(0));
+        assert_eq!(reservation.valid_till_block_height, 110);
+
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 100, accounts(4).as_str()));
+        assert_eq!(
+            contract.get_user_balance(&accounts(4), &"token_near".parse().unwrap()),
+            U128(200)
+        );
+        assert!(contract.get_transfer_reservation(U128(1)).is_none());
+    }
+
+    #[test]
+    fn test_quote_reservation_slashes_lp_stake_on_unlock() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(4));
+        let quote_id = publish_quote_as(&mut contract, accounts(4), 100, 1000);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                quote_id: Some(quote_id),
+                ..Default::default()
+            },
+        );
+
+        // The stake that is unbonding still backs the reservation
+        set_env!(predecessor_account_id: accounts(4));
+        contract.unregister_lp();
+
+        testing_env!(get_context_for_unlock(false));
+        contract.unlock_callback(true, U128(1));
+
+        assert_eq!(
+            contract.get_lp_unbonding(accounts(4)).unwrap().stake_amount,
+            U128(50)
+        );
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("fast_bridge_slash_lp_stake_event")
+                && log.contains("\"amount\":\"50\"")));
+    }
+
+    #[test]
+    fn test_best_quote_page() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(4));
+
+        publish_quote_as(&mut contract, accounts(4), 100, 50);
+        let quote_id = publish_quote_as(&mut contract, accounts(4), 100, 70);
+
+        let (best_quote_id, _) = contract
+            .get_best_quote("token_near".parse().unwrap(), U128(100), 1, 10)
+            .unwrap();
+        assert_eq!(best_quote_id, quote_id);
+        assert!(contract
+            .get_best_quote("token_near".parse().unwrap(), U128(100), 2, 10)
+            .is_none());
+    }
+
+    #[test]
+    fn test_init_transfer_bound_to_quote_uses_capacity() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(4));
+        let quote_id = publish_quote_as(&mut contract, accounts(4), 100, 1000);
+
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                quote_id: Some(quote_id),
+                ..Default::default()
+            },
+        );
+
+        // The quote is used up by the bound transfer
+        assert!(contract.get_quote(quote_id).is_none());
+        assert!(near_sdk::test_utils::get_logs().iter().any(|log| log
+            .contains("fast_bridge_bind_transfer_to_quote_event")
+            && log.contains("\"remaining_capacity\":\"0\"")));
+    }
+
+    #[test]
+    #[should_panic(expected = "The remaining capacity of the quote is 50")]
+    fn test_init_transfer_bound_to_quote_above_capacity() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(4));
+        deposit_transfer_storage(&mut contract, &accounts(4));
+        set_env!(predecessor_account_id: accounts(4));
+        let quote_id = contract.publish_quote(
+            "token_near".parse().unwrap(),
+            LP_ETH_RELAYER.to_string(),
+            U128(100),
+            U128(150),
+            1000,
+            1000,
+            100,
+        );
+        testing_env!(get_context(false));
+
+        let transfer_options = TransferOptions {
+            quote_id: Some(quote_id),
+            ..Default::default()
+        };
+        init_transfer_with_options(&mut contract, transfer_options.clone());
+        assert_eq!(
+            contract.get_quote(quote_id).unwrap().remaining_capacity,
+            U128(50)
+        );
+
+        init_transfer_with_options(&mut contract, transfer_options);
+    }
+
+    #[test]
+    #[should_panic(expected = "The LP of the quote isn't registered with the minimal stake")]
+    fn test_init_transfer_bound_to_quote_of_unstaked_lp() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(4));
+        let quote_id = publish_quote_as(&mut contract, accounts(4), 100, 1000);
+        contract.set_lp_stake_token("token_near".parse().unwrap(), Some(U128(200)));
+
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                quote_id: Some(quote_id),
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The fee should be at least 11 of the transfer token")]
+    fn test_init_transfer_below_quote_fee() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        register_lp_with_relayer(&mut contract, accounts(4));
+        let quote_id = publish_quote_as(&mut contract, accounts(4), 100, 1100);
+
+        contract.ft_on_transfer(signer_account_id(), U128(200), "".to_string());
+        deposit_transfer_storage(&mut contract, &signer_account_id());
+        let mut transfer_message = token_pair_transfer_message(&contract, eth_token_address());
+        transfer_message.fee.amount = U128(10);
+        contract.init_transfer_callback(
+            10,
+            transfer_message,
+            signer_account_id(),
+            None,
+            TransferOptions {
+                quote_id: Some(quote_id),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn swap_message(contract: &FastBridge, min_amount_out: u128) -> swap::SwapAndTransferMessage {
+        swap::SwapAndTransferMessage {
+            min_amount_out: U128(min_amount_out),
+            transfer_message: token_pair_transfer_message(contract, eth_token_address()),
+            transfer_options: TransferOptions::default(),
+        }
+    }
+
+    fn set_promise_result(result: &[u8]) {
+        testing_env!(
+            get_context(false),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![near_sdk::PromiseResult::Successful(result.to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_swap_callback_with_failed_swap() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        let swap_message = swap_message(&contract, 200);
+
+        set_promise_result(b"\"0\"");
+        let PromiseOrValue::Value(refund_amount) = contract.swap_callback(
+            signer_account_id(),
+            "token_in.near".parse().unwrap(),
+            U128(300),
+            "dex.near".parse().unwrap(),
+            U128(1),
+            swap_message,
+        ) else {
+            panic!("The failed swap should be refunded");
+        };
+        assert_eq!(refund_amount, U128(300));
+    }
+
+    fn swap_receipt(amount_out: u128) -> swap::SwapReceipt {
+        swap::SwapReceipt {
+            sender_id: signer_account_id(),
+            dex_account: "dex.near".parse().unwrap(),
+            token_in: "token_in.near".parse().unwrap(),
+            amount_in: U128(300),
+            token_out: "token_near".parse().unwrap(),
+            amount_out: U128(amount_out),
+        }
+    }
+
+    #[test]
+    fn test_swap_withdraw_callback() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        let swap_message = swap_message(&contract, 200);
+
+        set_promise_result(b"\"250\"");
+        let refund_amount =
+            contract.swap_withdraw_callback(U128(1), swap_receipt(250), Some(swap_message));
+
+        assert_eq!(refund_amount, U128(0));
+        assert_eq!(
+            contract.get_user_balance(&signer_account_id(), &"token_near".parse().unwrap()),
+            U128(250)
+        );
+        assert!(contract.get_swap_receipt(U128(1)).is_none());
+    }
+
+    #[test]
+    fn test_swap_withdraw_callback_with_failed_withdraw() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        let swap_message = swap_message(&contract, 200);
+
+        set_promise_result(b"\"0\"");
+        contract.swap_withdraw_callback(U128(1), swap_receipt(250), Some(swap_message));
+
+        assert_eq!(
+            contract.get_user_balance(&signer_account_id(), &"token_near".parse().unwrap()),
+            U128(0)
+        );
+        assert_eq!(contract.get_swap_receipt(U128(1)), Some(swap_receipt(250)));
+
+        set_env!(predecessor_account_id: signer_account_id());
+        contract.withdraw_swap_receipt(U128(1));
+        assert!(contract.get_swap_receipt(U128(1)).is_none());
+    }
+
+    #[test]
+    fn test_swap_receipt_callback_with_unknown_receipt() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        let swap_message = swap_message(&contract, 200);
+
+        set_promise_result(b"\"0\"");
+        let PromiseOrValue::Value(refund_amount) =
+            contract.swap_receipt_callback(U128(1), swap_receipt(0), Some(swap_message))
+        else {
+            panic!("The unknown receipt shouldn't be withdrawn");
+        };
+        assert_eq!(refund_amount, U128(0));
+        assert_eq!(contract.get_swap_receipt(U128(1)), Some(swap_receipt(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the sender of the swap can withdraw its output")]
+    fn test_withdraw_swap_receipt_by_other_account() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        let swap_message = swap_message(&contract, 200);
+
+        set_promise_result(b"\"0\"");
+        contract.swap_withdraw_callback(U128(1), swap_receipt(250), Some(swap_message));
+
+        set_env!(predecessor_account_id: accounts(3));
+        contract.withdraw_swap_receipt(U128(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "The minimal swap output should cover the transfer amount 200")]
+    fn test_swap_with_min_amount_out_below_transfer() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_dex_account(Some("dex.near".parse().unwrap()));
+
+        let msg = near_sdk::base64::encode(swap_message(&contract, 150).try_to_vec().unwrap());
+        contract.ft_on_transfer(signer_account_id(), U128(300), format!("swap:{}", msg));
+    }
+
+    #[test]
+    fn test_lp_unlock_with_native_fee() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                native_fee_amount: Some(U128(1000)),
+                ..Default::default()
+            },
+        );
+
+        let storage_bond = contract.get_transfer_storage_bond(U128(1)).0;
+        assert_eq!(
+            contract.get_transfer_storage_balance(&signer_account_id()),
+            U128(near_sdk::ONE_NEAR - storage_bond - 1000)
+        );
+        assert_eq!(contract.get_pending_native_fee_balance(), U128(1000));
+
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 100, relayer.as_str()));
+
+        assert_eq!(contract.get_transfer_storage_balance(&relayer), U128(0));
+        assert_eq!(contract.get_native_fee_balance(relayer.clone()), U128(1000));
+        assert_eq!(contract.get_pending_native_fee_balance(), U128(0));
+
+        set_env!(predecessor_account_id: relayer.clone(), attached_deposit: 1);
+        contract.withdraw_native_fee(Some(U128(400)));
+        assert_eq!(contract.get_native_fee_balance(relayer.clone()), U128(600));
+        contract.withdraw_native_fee(None);
+        assert_eq!(contract.get_native_fee_balance(relayer), U128(0));
+    }
+
+    #[test]
+    fn test_lp_unlock_with_native_fee_only() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.ft_on_transfer(signer_account_id(), U128(100), "".to_string());
+        deposit_transfer_storage(&mut contract, &signer_account_id());
+        let mut transfer_message = token_pair_transfer_message(&contract, eth_token_address());
+        transfer_message.fee.amount = U128(0);
+        contract.init_transfer_callback(
+            10,
+            transfer_message,
+            signer_account_id(),
+            None,
+            TransferOptions {
+                native_fee_amount: Some(U128(1000)),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            contract.get_user_balance(&signer_account_id(), &"token_near".parse().unwrap()),
+            U128(0)
+        );
+
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 100, relayer.as_str()));
+
+        assert_eq!(contract.get_native_fee_balance(relayer), U128(1000));
+        assert_eq!(contract.get_pending_native_fee_balance(), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient native fee balance")]
+    fn test_withdraw_native_fee_exceeding_balance() {
+        set_env!(predecessor_account_id: accounts(1), attached_deposit: 1);
+        let mut contract = get_bridge_contract(None);
+        contract.withdraw_native_fee(Some(U128(1)));
+    }
+
+    #[test]
+    fn test_unlock_refunds_native_fee() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                native_fee_amount: Some(U128(1000)),
+                ..Default::default()
+            },
+        );
+
+        testing_env!(get_context_for_unlock(false));
+        contract.unlock_callback(true, U128(1));
+
+        assert_eq!(
+            contract.get_transfer_storage_balance(&signer_account_id()),
+            U128(near_sdk::ONE_NEAR)
+        );
+        assert_eq!(contract.get_pending_native_fee_balance(), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "The native fee isn't supported by the partially fillable transfers")]
+    fn test_native_fee_with_partial_fills() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                allow_partial_fills: true,
+                native_fee_amount: Some(U128(1000)),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn init_transfer_with_integrator_fee(contract: &mut FastBridge, integrator_fee_amount: u128) {
+        contract.acl_grant_role("ConfigManager".to_string(), "token_near".parse().unwrap());
+        contract.set_max_integrator_fee_bps(100);
+        // The integrator fee is paid on top of the transfer amount and the fee
+        contract.ft_on_transfer(signer_account_id(), U128(10), "".to_string());
+        init_transfer_with_options(
+            contract,
+            TransferOptions {
+                integrator_fee: Some(integrator_fee::IntegratorFee {
+                    account_id: accounts(5),
+                    amount: U128(integrator_fee_amount),
+                }),
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_lp_unlock_with_integrator_fee() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_integrator_fee(&mut contract, 1);
+
+        let token: AccountId = "token_near".parse().unwrap();
+        assert_eq!(
+            contract.get_user_balance(&signer_account_id(), &token),
+            U128(9)
+        );
+
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 100, relayer.as_str()));
+
+        assert_eq!(contract.get_user_balance(&relayer, &token), U128(200));
+        assert_eq!(contract.get_user_balance(&accounts(5), &token), U128(1));
+        assert_eq!(
+            contract.get_integrator_stats(accounts(5), token),
+            integrator_fee::IntegratorStats {
+                transfer_count: 1,
+                volume: U128(100),
+                fee_amount: U128(1),
+            }
+        );
+    }
+
+    #[test]
+    fn test_unlock_refunds_integrator_fee() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_integrator_fee(&mut contract, 1);
+
+        testing_env!(get_context_for_unlock(false));
+        let transfer_message = contract.unlock_callback(true, U128(1));
+
+        assert_eq!(transfer_message.transfer.amount, U128(100));
+        assert_eq!(
+            contract.get_user_balance(&signer_account_id(), &"token_near".parse().unwrap()),
+            U128(210)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The integrator fee can't exceed 1 of the transfer token")]
+    fn test_integrator_fee_above_max() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_integrator_fee(&mut contract, 2);
+    }
+
+    fn payload_hash() -> [u8; 32] {
+        env::keccak256(b"payload").try_into().unwrap()
+    }
+
+    fn payload_transfer_event(unlock_recipient: &str) -> EthTransferEvent {
+        EthTransferEvent {
+            transfer_id: utils::get_transfer_id(
+                get_eth_address(eth_token_address()),
+                get_eth_address(eth_recipient_address()),
+                eth_types::U256(1u128.into()),
+                eth_types::U256(100u128.into()),
+                Some(payload_hash()),
+            )
+            .try_into()
+            .unwrap(),
+            payload_hash: Some(payload_hash()),
+            ..eth_transfer_event(1, 100, unlock_recipient)
+        }
+    }
+
+    #[test]
+    fn test_lp_unlock_with_payload() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                payload_hash: Some(payload_hash()),
+                ..Default::default()
+            },
+        );
+
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        contract.verify_log_entry_callback(true, payload_transfer_event(relayer.as_str()));
+
+        assert_eq!(
+            contract.get_user_balance(&relayer, &"token_near".parse().unwrap()),
+            U128(200)
+        );
+        assert!(contract.get_pending_transfer("1".to_string()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong payload hash of the transfer")]
+    fn test_lp_unlock_without_payload() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                payload_hash: Some(payload_hash()),
+                ..Default::default()
+            },
+        );
+
+        contract.verify_log_entry_callback(true, eth_transfer_event(1, 100, "relayer.near"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong transfer id of the transfer with the payload")]
+    fn test_lp_unlock_with_wrong_payload_transfer_id() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        init_transfer_with_options(
+            &mut contract,
+            TransferOptions {
+                payload_hash: Some(payload_hash()),
+                ..Default::default()
+            },
+        );
+
+        contract.verify_log_entry_callback(
+            true,
+            EthTransferEvent {
+                transfer_id: vec![0u8; 32].try_into().unwrap(),
+                ..payload_transfer_event("relayer.near")
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The payload isn't supported by the partially fillable transfers")]
+    fn test_payload_with_partial_fills() {
+        testing_env!(get_context(false));
+        let mut contract = get_bridge_contract(None);
+        contract.ft_on_transfer(signer_account_id(), U128(200), "".to_string());
+        contract.init_transfer_callback(
+            10,
+            token_pair_transfer_message(&contract, eth_token_address()),
+            signer_account_id(),
+            None,
+            TransferOptions {
+                allow_partial_fills: true,
+                payload_hash: Some(payload_hash()),
+                ..Default::default()
+            },
+        );
+    }
 }
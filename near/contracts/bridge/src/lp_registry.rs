@@ -0,0 +1,444 @@
+use near_plugins::access_control_any;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId};
+
+use crate::*;
+
+/// The LP that staked a bond to be eligible for the fees in the registry mode.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RegisteredLp {
+    pub stake_token: AccountId,
+    pub stake_amount: U128,
+    /// The Ethereum addresses that fill the transfers on behalf of the LP
+    pub eth_relayers: Vec<EthAddress>,
+}
+
+/// The Ethereum relayer declared by the LP together with the proof that the LP controls it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LpEthRelayer {
+    /// The hex-encoded Ethereum address of the relayer
+    pub eth_relayer: String,
+    /// The hex-encoded signature of the relayer over `get_relayer_registration_message()` (EIP-191)
+    pub signature: String,
+}
+
+/// The stake of the unregistered LP that can still be slashed for the transfers it reserved.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LpUnbonding {
+    pub stake_token: AccountId,
+    pub stake_amount: U128,
+    /// The timestamp when the stake can be withdrawn
+    pub unbonded_at: u64,
+}
+
+#[near_bindgen]
+impl FastBridge {
+    /// Registers the caller as an LP by moving the `stake_amount` of the `stake_token` from its balance
+    /// in this contract to the stake. The fills of the declared `eth_relayers` that are claimed to the caller
+    /// are eligible for the fees when the registry mode is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `stake_token` - The token of the stake, it should be allowed with `set_lp_stake_token()`.
+    /// * `stake_amount` - The amount of the stake, at least the minimal stake of the token.
+    /// * `eth_relayers` - The Ethereum addresses of the LP relayers, each signed by the relayer key over
+    ///   the LP account, this contract and the current nonce of the relayer, see `get_eth_relayer_nonce()`.
+    #[pause]
+    pub fn register_lp(
+        &mut self,
+        stake_token: AccountId,
+        stake_amount: U128,
+        eth_relayers: Vec<LpEthRelayer>,
+    ) -> RegisteredLp {
+        let account_id = env::predecessor_account_id();
+        self.check_account_not_blocked(&account_id);
+        require!(
+            self.registered_lps.get(&account_id).is_none(),
+            "The LP is already registered"
+        );
+
+        let min_stake = self
+            .lp_stake_tokens
+            .get(&stake_token)
+            .unwrap_or_else(|| env::panic_str("The token can't be used for the LP stake"));
+        require!(
+            stake_amount.0 >= min_stake,
+            format!("The LP stake should be at least {}", min_stake)
+        );
+        self.take_lp_stake(&account_id, &stake_token, stake_amount.0);
+
+        let mut lp = RegisteredLp {
+            stake_token,
+            stake_amount,
+            eth_relayers: vec![],
+        };
+        self.add_eth_relayers_internal(&account_id, &mut lp, eth_relayers);
+        self.registered_lps.insert(&account_id, &lp);
+
+        FastBridgeEvent::FastBridgeRegisterLpEvent {
+            account_id,
+            lp: lp.clone(),
+        }
+        .emit();
+
+        lp
+    }
+
+    /// Moves the `amount` of the stake token from the balance of the registered LP to its stake.
+    #[pause]
+    pub fn increase_lp_stake(&mut self, amount: U128) -> RegisteredLp {
+        let account_id = env::predecessor_account_id();
+        let mut lp = self.get_registered_lp_or_panic(&account_id);
+        self.take_lp_stake(&account_id, &lp.stake_token, amount.0);
+        lp.stake_amount = U128(lp.stake_amount.0 + amount.0);
+
+        self.update_registered_lp(account_id, lp)
+    }
+
+    /// Declares additional Ethereum relayers of the registered LP.
+    #[pause]
+    pub fn add_lp_eth_relayers(&mut self, eth_relayers: Vec<LpEthRelayer>) -> RegisteredLp {
+        let account_id = env::predecessor_account_id();
+        let mut lp = self.get_registered_lp_or_panic(&account_id);
+        self.add_eth_relayers_internal(&account_id, &mut lp, eth_relayers);
+
+        self.update_registered_lp(account_id, lp)
+    }
+
+    /// Removes the Ethereum relayers of the registered LP.
+    pub fn remove_lp_eth_relayers(&mut self, eth_relayers: Vec<String>) -> RegisteredLp {
+        let account_id = env::predecessor_account_id();
+        let mut lp = self.get_registered_lp_or_panic(&account_id);
+        for eth_relayer in eth_relayers {
+            let eth_relayer = get_eth_address(eth_relayer);
+            if self.lp_eth_relayers.get(&eth_relayer) == Some(account_id.clone()) {
+                self.lp_eth_relayers.remove(&eth_relayer);
+            }
+            lp.eth_relayers.retain(|relayer| *relayer != eth_relayer);
+        }
+
+        self.update_registered_lp(account_id, lp)
+    }
+
+    /// Unregisters the caller, removes its quotes and starts the unbonding of its stake. The stake can be withdrawn with
+    /// `withdraw_lp_stake()` after the `lock_time_max`, the longest lock period of the pending transfers,
+    /// so it can still be slashed for the transfers that the LP reserved before.
+    pub fn unregister_lp(&mut self) -> LpUnbonding {
+        let account_id = env::predecessor_account_id();
+        let lp = self.get_registered_lp_or_panic(&account_id);
+        require!(
+            !self.lp_unbondings.contains_key(&account_id),
+            "The previous LP stake is still unbonding"
+        );
+        for eth_relayer in lp.eth_relayers.iter() {
+            self.lp_eth_relayers.remove(eth_relayer);
+        }
+        self.registered_lps.remove(&account_id);
+        self.remove_lp_quotes(&account_id);
+
+        let unbonding = LpUnbonding {
+            stake_token: lp.stake_token.clone(),
+            stake_amount: lp.stake_amount,
+            unbonded_at: block_timestamp() + self.lock_duration.lock_time_max,
+        };
+        self.lp_unbondings.insert(&account_id, &unbonding);
+
+        FastBridgeEvent::FastBridgeUnregisterLpEvent { account_id, lp }.emit();
+
+        unbonding
+    }
+
+    /// Moves the unbonded stake of the unregistered caller to its balance in this contract.
+    pub fn withdraw_lp_stake(&mut self) -> U128 {
+        let account_id = env::predecessor_account_id();
+        let unbonding = self
+            .lp_unbondings
+            .get(&account_id)
+            .unwrap_or_else(|| env::panic_str("The LP stake isn't unbonding"));
+        require!(
+            block_timestamp() >= unbonding.unbonded_at,
+            "The LP stake is still unbonding"
+        );
+        self.lp_unbondings.remove(&account_id);
+        self.increase_balance(
+            &account_id,
+            &unbonding.stake_token,
+            &unbonding.stake_amount.0,
+        );
+
+        FastBridgeEvent::FastBridgeWithdrawLpStakeEvent {
+            account_id,
+            token: unbonding.stake_token,
+            amount: unbonding.stake_amount,
+        }
+        .emit();
+
+        unbonding.stake_amount
+    }
+
+    /// Allows the `token` to be used for the LP stakes with the `min_stake`, `None` disallows the token.
+    /// The LPs with a stake below the minimum aren't eligible for the fees.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn set_lp_stake_token(&mut self, token: AccountId, min_stake: Option<U128>) {
+        let previous_min_stake = match min_stake {
+            Some(min_stake) => self.lp_stake_tokens.insert(&token, &min_stake.0),
+            None => self.lp_stake_tokens.remove(&token),
+        };
+
+        FastBridgeEvent::FastBridgeSetLpStakeTokenEvent {
+            token,
+            previous_min_stake: previous_min_stake.map(U128),
+            min_stake,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    /// Enables or disables the registry mode. In the registry mode the fees are paid only to the registered LPs
+    /// for the fills of their relayers, the fees of other fills are returned to the originators of the transfers.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn set_lp_registry_enabled(&mut self, enabled: bool) {
+        let previous_enabled = self.is_lp_registry_enabled;
+        self.is_lp_registry_enabled = enabled;
+
+        FastBridgeEvent::FastBridgeSetLpRegistryEnabledEvent {
+            previous_enabled,
+            enabled,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn is_lp_registry_enabled(&self) -> bool {
+        self.is_lp_registry_enabled
+    }
+
+    pub fn get_lp_stake_tokens(&self) -> Vec<(AccountId, U128)> {
+        self.lp_stake_tokens
+            .iter()
+            .map(|(token, min_stake)| (token, U128(min_stake)))
+            .collect::<Vec<_>>()
+    }
+
+    pub fn get_registered_lp(&self, account_id: AccountId) -> Option<RegisteredLp> {
+        self.registered_lps.get(&account_id)
+    }
+
+    pub fn get_lp_unbonding(&self, account_id: AccountId) -> Option<LpUnbonding> {
+        self.lp_unbondings.get(&account_id)
+    }
+
+    /// Returns a page of the registered LPs, starting at the specified `from_index`
+    /// and containing a maximum of `limit` LPs.
+    pub fn get_registered_lps(
+        &self,
+        from_index: usize,
+        limit: usize,
+    ) -> Vec<(AccountId, RegisteredLp)> {
+        self.registered_lps
+            .iter()
+            .skip(from_index)
+            .take(limit)
+            .collect::<Vec<_>>()
+    }
+
+    /// Gets the LP account that declared the hex-encoded Ethereum `eth_relayer`.
+    pub fn get_lp_by_eth_relayer(&self, eth_relayer: String) -> Option<AccountId> {
+        self.lp_eth_relayers.get(&get_eth_address(eth_relayer))
+    }
+
+    /// Gets the nonce that the hex-encoded Ethereum `eth_relayer` should sign to be declared by an LP.
+    pub fn get_eth_relayer_nonce(&self, eth_relayer: String) -> u64 {
+        self.eth_relayer_nonces
+            .get(&get_eth_address(eth_relayer))
+            .unwrap_or(0)
+    }
+}
+
+impl FastBridge {
+    /// Credits the LP fee of the fill to the `recipient_id` if it's eligible for the fee,
+    /// otherwise the fee is returned to the `originator_id` of the transfer.
+    /// Returns the fee amount credited to the `recipient_id`.
+    pub(crate) fn credit_lp_fee(
+        &mut self,
+        nonce: U128,
+        recipient_id: &AccountId,
+        originator_id: &AccountId,
+        eth_relayer: &EthAddress,
+        fee_token: &AccountId,
+        fee_amount: u128,
+    ) -> u128 {
+        if self.is_eligible_for_lp_fee(recipient_id, eth_relayer) {
+            self.increase_balance(recipient_id, fee_token, &fee_amount);
+            return fee_amount;
+        }
+
+        self.increase_balance(originator_id, fee_token, &fee_amount);
+
+        FastBridgeEvent::FastBridgeLpFeeRefundEvent {
+            nonce,
+            lp_account: recipient_id.clone(),
+            recipient_id: originator_id.clone(),
+            token: fee_token.clone(),
+            amount: U128(fee_amount),
+        }
+        .emit();
+
+        0
+    }
+
+    /// Checks whether the `unlock_recipient` is eligible for the fee of the fill made by the `eth_relayer`.
+    /// All LPs are eligible if the registry mode is disabled.
+    pub(crate) fn is_eligible_for_lp_fee(
+        &self,
+        unlock_recipient: &AccountId,
+        eth_relayer: &EthAddress,
+    ) -> bool {
+        !self.is_lp_registry_enabled || self.is_staked_lp_relayer(unlock_recipient, eth_relayer)
+    }
+
+    /// Checks whether the `account_id` is the registered LP with at least the minimal stake
+    /// that declared the `eth_relayer`.
+    pub(crate) fn is_staked_lp_relayer(
+        &self,
+        account_id: &AccountId,
+        eth_relayer: &EthAddress,
+    ) -> bool {
+        let Some(lp) = self.registered_lps.get(account_id) else {
+            return false;
+        };
+        let min_stake = self.lp_stake_tokens.get(&lp.stake_token);
+
+        lp.eth_relayers.contains(eth_relayer)
+            && min_stake.map_or(false, |min_stake| lp.stake_amount.0 >= min_stake)
+    }
+
+    /// Slashes the stake of the `lp_account` to the `recipient_id` for the transfer with the `nonce`.
+    /// The minimal stake of the token is slashed, or the whole stake if the token is no longer allowed.
+    /// The stake that is unbonding is slashed if the LP is unregistered.
+    pub(crate) fn slash_lp_stake(
+        &mut self,
+        nonce: U128,
+        lp_account: &AccountId,
+        recipient_id: &AccountId,
+    ) {
+        let (token, amount) = if let Some(mut lp) = self.registered_lps.get(lp_account) {
+            let amount = self.get_lp_slash_amount(&lp.stake_token, lp.stake_amount.0);
+            lp.stake_amount = U128(lp.stake_amount.0 - amount);
+            self.registered_lps.insert(lp_account, &lp);
+            (lp.stake_token, amount)
+        } else if let Some(mut unbonding) = self.lp_unbondings.get(lp_account) {
+            let amount = self.get_lp_slash_amount(&unbonding.stake_token, unbonding.stake_amount.0);
+            unbonding.stake_amount = U128(unbonding.stake_amount.0 - amount);
+            self.lp_unbondings.insert(lp_account, &unbonding);
+            (unbonding.stake_token, amount)
+        } else {
+            return;
+        };
+        if amount == 0 {
+            return;
+        }
+        self.increase_balance(recipient_id, &token, &amount);
+
+        FastBridgeEvent::FastBridgeSlashLpStakeEvent {
+            nonce,
+            lp_account: lp_account.clone(),
+            recipient_id: recipient_id.clone(),
+            token,
+            amount: U128(amount),
+        }
+        .emit();
+    }
+
+    fn get_lp_slash_amount(&self, stake_token: &AccountId, stake_amount: u128) -> u128 {
+        self.lp_stake_tokens
+            .get(stake_token)
+            .map_or(stake_amount, |min_stake| {
+                std::cmp::min(min_stake, stake_amount)
+            })
+    }
+
+    fn get_registered_lp_or_panic(&self, account_id: &AccountId) -> RegisteredLp {
+        self.registered_lps
+            .get(account_id)
+            .unwrap_or_else(|| env::panic_str("The LP is not registered"))
+    }
+
+    fn take_lp_stake(&mut self, account_id: &AccountId, token: &AccountId, amount: u128) {
+        require!(amount > 0, "The amount should be a positive number");
+        require!(
+            self.get_user_balance(account_id, token).0 >= amount,
+            "Not enough balance for the LP stake"
+        );
+        self.decrease_balance(account_id, token, &amount);
+    }
+
+    fn add_eth_relayers_internal(
+        &mut self,
+        account_id: &AccountId,
+        lp: &mut RegisteredLp,
+        eth_relayers: Vec<LpEthRelayer>,
+    ) {
+        for LpEthRelayer {
+            eth_relayer,
+            signature,
+        } in eth_relayers
+        {
+            let eth_relayer = get_eth_address(eth_relayer);
+            if let Some(owner_id) = self.lp_eth_relayers.get(&eth_relayer) {
+                require!(
+                    owner_id == *account_id,
+                    format!(
+                        "The relayer `{}` is declared by another LP",
+                        hex::encode(eth_relayer.0)
+                    )
+                );
+                continue;
+            }
+
+            let nonce = self.eth_relayer_nonces.get(&eth_relayer).unwrap_or(0);
+            let message = eth_signature::get_relayer_registration_message(
+                &env::current_account_id(),
+                account_id,
+                nonce,
+            );
+            let signature = hex::decode(signature.trim_start_matches("0x"))
+                .unwrap_or_else(|_| env::panic_str("Invalid hex format of the signature"));
+            let signer = eth_signature::recover_eth_address(
+                &eth_signature::personal_message_hash(message.as_bytes()),
+                &signature,
+            );
+            require!(
+                signer.as_ref() == Some(&eth_relayer),
+                format!(
+                    "The relayer `{}` didn't sign the declaration",
+                    hex::encode(eth_relayer.0)
+                )
+            );
+
+            self.eth_relayer_nonces.insert(&eth_relayer, &(nonce + 1));
+            self.lp_eth_relayers.insert(&eth_relayer, account_id);
+            lp.eth_relayers.push(eth_relayer);
+        }
+    }
+
+    fn update_registered_lp(&mut self, account_id: AccountId, lp: RegisteredLp) -> RegisteredLp {
+        self.registered_lps.insert(&account_id, &lp);
+
+        FastBridgeEvent::FastBridgeUpdateLpEvent {
+            account_id,
+            lp: lp.clone(),
+        }
+        .emit();
+
+        lp
+    }
+}
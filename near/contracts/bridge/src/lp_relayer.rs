@@ -2,10 +2,16 @@ use eth_types::{LogEntry, H256};
 use ethabi::{Event, EventParam, Hash, ParamType, RawLog};
 use fast_bridge_common::*;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::AccountId;
 
 type EthEventParams = Vec<(String, ParamType, bool)>;
 
 const EVENT_NAME: &str = "TransferTokens";
+const PARTIAL_EVENT_NAME: &str = "TransferTokensPartially";
+const PAYLOAD_EVENT_NAME: &str = "TransferTokensWithPayload";
+const AURORA_RECIPIENT_PREFIX: &str = "aurora:";
+/// The index of the block number in the RLP-encoded Ethereum block header
+const HEADER_NUMBER_INDEX: usize = 8;
 
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct EthTransferEvent {
@@ -15,8 +21,14 @@ pub struct EthTransferEvent {
     pub token: EthAddress,
     pub recipient: EthAddress,
     pub amount: u128,
+    /// The total amount filled after the partial fill, `None` if the transfer was filled completely at once
+    pub filled_amount: Option<u128>,
     pub unlock_recipient: String,
     pub transfer_id: H256,
+    /// The hash of the payload the recipient was called with, `None` if the transfer was filled without the payload
+    pub payload_hash: Option<[u8; 32]>,
+    /// The Ethereum block height of the event, `None` if it isn't known from the block header of the proof
+    pub eth_block_height: Option<u64>,
 }
 
 impl EthTransferEvent {
@@ -32,11 +44,35 @@ impl EthTransferEvent {
         ]
     }
 
-    pub fn parse(proof: Proof) -> Self {
-        let data = proof.log_entry_data;
-        let params = EthTransferEvent::event_params();
-        let event = Event {
-            name: EVENT_NAME.to_string(),
+    pub fn partial_event_params() -> EthEventParams {
+        vec![
+            ("nonce".to_string(), ParamType::Uint(256), true),
+            ("relayer".to_string(), ParamType::Address, false),
+            ("token".to_string(), ParamType::Address, false),
+            ("recipient".to_string(), ParamType::Address, false),
+            ("fill_amount".to_string(), ParamType::Uint(256), false),
+            ("filled_amount".to_string(), ParamType::Uint(256), false),
+            ("unlock_recipient".to_string(), ParamType::String, false),
+            ("transfer_id".to_string(), ParamType::FixedBytes(32), true),
+        ]
+    }
+
+    pub fn payload_event_params() -> EthEventParams {
+        vec![
+            ("nonce".to_string(), ParamType::Uint(256), true),
+            ("relayer".to_string(), ParamType::Address, false),
+            ("token".to_string(), ParamType::Address, false),
+            ("recipient".to_string(), ParamType::Address, false),
+            ("amount".to_string(), ParamType::Uint(256), false),
+            ("unlock_recipient".to_string(), ParamType::String, false),
+            ("payload_hash".to_string(), ParamType::FixedBytes(32), false),
+            ("transfer_id".to_string(), ParamType::FixedBytes(32), true),
+        ]
+    }
+
+    fn event(name: &str, params: EthEventParams) -> Event {
+        Event {
+            name: name.to_string(),
             inputs: params
                 .into_iter()
                 .map(|(name, kind, indexed)| EventParam {
@@ -46,7 +82,16 @@ impl EthTransferEvent {
                 })
                 .collect(),
             anonymous: false,
-        };
+        }
+    }
+
+    /// Parses either the `TransferTokens`, the `TransferTokensPartially` or the `TransferTokensWithPayload`
+    /// event from the proof.
+    pub fn parse(proof: Proof) -> Self {
+        let eth_block_height = rlp::Rlp::new(&proof.header_data)
+            .val_at::<u64>(HEADER_NUMBER_INDEX)
+            .ok();
+        let data = proof.log_entry_data;
 
         let log_entry: LogEntry = rlp::decode(&data).expect("Invalid RLP");
         let locker_address = (log_entry.address.0).0;
@@ -59,7 +104,31 @@ impl EthTransferEvent {
             topics,
             data: log_entry.data,
         };
-        let log = event.parse_log(raw_log).expect("Failed to parse event log");
+
+        let partial_event = Self::event(PARTIAL_EVENT_NAME, Self::partial_event_params());
+        let payload_event = Self::event(PAYLOAD_EVENT_NAME, Self::payload_event_params());
+        let is_partial_fill = raw_log.topics.first() == Some(&partial_event.signature());
+        let has_payload = raw_log.topics.first() == Some(&payload_event.signature());
+        let event = if is_partial_fill {
+            partial_event
+        } else if has_payload {
+            payload_event
+        } else {
+            Self::event(EVENT_NAME, Self::event_params())
+        };
+
+        let mut log = event.parse_log(raw_log).expect("Failed to parse event log");
+        let filled_amount =
+            is_partial_fill.then(|| log.params.remove(5).value.to_uint().unwrap().as_u128());
+        let payload_hash: Option<[u8; 32]> = has_payload.then(|| {
+            log.params
+                .remove(6)
+                .value
+                .to_fixed_bytes()
+                .unwrap()
+                .try_into()
+                .unwrap()
+        });
         let nonce = log.params[0].value.clone().to_uint().unwrap().as_u128();
 
         let relayer = log.params[1].value.clone().to_address().unwrap().0;
@@ -82,8 +151,41 @@ impl EthTransferEvent {
             token: EthAddress(token),
             recipient: EthAddress(recipient),
             amount,
+            filled_amount,
             unlock_recipient,
             transfer_id,
+            payload_hash,
+            eth_block_height,
+        }
+    }
+}
+
+/// The recipient of the unlocked tokens, encoded in the `unlock_recipient` of the transfer event.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnlockRecipient {
+    Near(AccountId),
+    /// The Aurora EVM address in the form `aurora:0x{address}`
+    Aurora(EthAddress),
+}
+
+impl UnlockRecipient {
+    /// Parses the `unlock_recipient`, `None` if it's neither a NEAR account nor an Aurora address.
+    pub fn parse(unlock_recipient: &str) -> Option<Self> {
+        let Some(aurora_address) = unlock_recipient.strip_prefix(AURORA_RECIPIENT_PREFIX) else {
+            return unlock_recipient.parse().ok().map(Self::Near);
+        };
+
+        let address = hex::decode(aurora_address.strip_prefix("0x")?).ok()?;
+        Some(Self::Aurora(EthAddress(address.try_into().ok()?)))
+    }
+
+    /// The NEAR account that is credited with the unlocked tokens. The tokens of the Aurora address are credited
+    /// to its Ethereum-implicit account (NEP-518) and then withdrawn to Aurora, so the failed withdrawals
+    /// stay on the balance that the owner of the address controls.
+    pub fn account_id(&self) -> AccountId {
+        match self {
+            Self::Near(account_id) => account_id.clone(),
+            Self::Aurora(address) => format!("0x{}", hex::encode(address.0)).parse().unwrap(),
         }
     }
 }
@@ -152,21 +254,34 @@ mod tests {
     }
 
     fn to_log_entry_data(event: &EthTransferEvent) -> Vec<u8> {
+        let mut values = vec![
+            Token::Address(event.relayer.0.into()),
+            Token::Address(event.token.0.into()),
+            Token::Address(event.recipient.0.into()),
+            Token::Uint(event.amount.into()),
+            Token::String(event.unlock_recipient.clone()),
+        ];
+        let (name, params) = match (event.filled_amount, event.payload_hash) {
+            (Some(filled_amount), _) => {
+                values.insert(4, Token::Uint(filled_amount.into()));
+                (PARTIAL_EVENT_NAME, EthTransferEvent::partial_event_params())
+            }
+            (None, Some(payload_hash)) => {
+                values.push(Token::FixedBytes(payload_hash.to_vec()));
+                (PAYLOAD_EVENT_NAME, EthTransferEvent::payload_event_params())
+            }
+            (None, None) => (EVENT_NAME, EthTransferEvent::event_params()),
+        };
+
         EthEvent::to_log_entry_data(
-            EVENT_NAME,
-            EthTransferEvent::event_params(),
+            name,
+            params,
             event.eth_bridge_contract,
             vec![
                 event.nonce.to_be_bytes().to_vec(),
                 event.transfer_id.0 .0.to_vec(),
             ],
-            vec![
-                Token::Address(event.relayer.0.into()),
-                Token::Address(event.token.0.into()),
-                Token::Address(event.recipient.0.into()),
-                Token::Uint(event.amount.into()),
-                Token::String(event.unlock_recipient.clone()),
-            ],
+            values,
         )
     }
 
@@ -203,9 +318,12 @@ mod tests {
             recipient,
             nonce,
             amount,
+            filled_amount: None,
             transfer_id: keccak256(transfer_id.as_slice()).try_into().unwrap(),
             relayer,
             unlock_recipient: "unlocker.near".to_string(),
+            payload_hash: None,
+            eth_block_height: None,
         };
 
         let proof: Proof = create_proof(&transfer_event);
@@ -216,5 +334,100 @@ mod tests {
         assert_eq!(token, param.token);
         assert_eq!(recipient, param.recipient);
         assert_eq!(amount, param.amount);
+        assert_eq!(None, param.filled_amount);
+        assert_eq!(None, param.payload_hash);
+        assert_eq!(None, param.eth_block_height);
+    }
+
+    #[test]
+    fn test_payload_event_data() {
+        let eth_bridge_contract =
+            get_eth_address("0aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string());
+        let transfer_event = EthTransferEvent {
+            eth_bridge_contract,
+            nonce: 200,
+            relayer: get_eth_address("1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string()),
+            token: get_eth_address("2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string()),
+            recipient: get_eth_address("3aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string()),
+            amount: 161,
+            filled_amount: None,
+            transfer_id: keccak256(b"transfer_id").try_into().unwrap(),
+            unlock_recipient: "unlocker.near".to_string(),
+            payload_hash: Some(keccak256(b"payload").try_into().unwrap()),
+            eth_block_height: None,
+        };
+
+        let param = EthTransferEvent::parse(create_proof(&transfer_event));
+
+        assert_eq!(transfer_event.nonce, param.nonce);
+        assert_eq!(transfer_event.amount, param.amount);
+        assert_eq!(None, param.filled_amount);
+        assert_eq!(transfer_event.unlock_recipient, param.unlock_recipient);
+        assert_eq!(transfer_event.transfer_id.0 .0, param.transfer_id.0 .0);
+        assert_eq!(transfer_event.payload_hash, param.payload_hash);
+    }
+
+    #[test]
+    fn test_partial_event_data() {
+        let eth_bridge_contract =
+            get_eth_address("0aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string());
+        let transfer_event = EthTransferEvent {
+            eth_bridge_contract,
+            nonce: 200,
+            relayer: get_eth_address("1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string()),
+            token: get_eth_address("2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string()),
+            recipient: get_eth_address("3aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string()),
+            amount: 40,
+            filled_amount: Some(100),
+            transfer_id: keccak256(b"transfer_id").try_into().unwrap(),
+            unlock_recipient: "unlocker.near".to_string(),
+            payload_hash: None,
+            eth_block_height: Some(1234),
+        };
+
+        let mut header = rlp::RlpStream::new_list(HEADER_NUMBER_INDEX + 1);
+        for _ in 0..HEADER_NUMBER_INDEX {
+            header.append_empty_data();
+        }
+        header.append(&1234u64);
+        let proof = Proof {
+            header_data: header.out().to_vec(),
+            ..create_proof(&transfer_event)
+        };
+        let param = EthTransferEvent::parse(proof);
+
+        assert_eq!(transfer_event.nonce, param.nonce);
+        assert_eq!(transfer_event.amount, param.amount);
+        assert_eq!(Some(100), param.filled_amount);
+        assert_eq!(transfer_event.unlock_recipient, param.unlock_recipient);
+        assert_eq!(transfer_event.eth_block_height, param.eth_block_height);
+    }
+
+    #[test]
+    fn test_parse_unlock_recipient() {
+        assert_eq!(
+            UnlockRecipient::parse("unlocker.near"),
+            Some(UnlockRecipient::Near("unlocker.near".parse().unwrap()))
+        );
+
+        let aurora_recipient =
+            UnlockRecipient::parse("aurora:0x1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+        assert_eq!(
+            aurora_recipient,
+            UnlockRecipient::Aurora(get_eth_address(
+                "1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string()
+            ))
+        );
+        assert_eq!(
+            aurora_recipient.account_id().as_str(),
+            "0x1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+        );
+
+        assert_eq!(UnlockRecipient::parse("Unlocker.near"), None);
+        assert_eq!(
+            UnlockRecipient::parse("aurora:1aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+            None
+        );
+        assert_eq!(UnlockRecipient::parse("aurora:0x1aaa"), None);
     }
 }
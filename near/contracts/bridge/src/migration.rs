@@ -0,0 +1,161 @@
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet};
+use near_sdk::env;
+
+use crate::*;
+
+/// The state of the deployed contract before the pending transfer details and the settings that followed them
+/// were introduced. Every field added to `FastBridge` since then gets its default value in `from()`.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct FastBridgeV0 {
+    pub(crate) pending_transfers: UnorderedMap<String, (AccountId, TransferMessage)>,
+    pub(crate) token_balances: LookupMap<AccountId, LookupMap<AccountId, u128>>,
+    pub(crate) nonce: u128,
+    pub(crate) prover_account: AccountId,
+    pub(crate) eth_client_account: AccountId,
+    pub(crate) eth_bridge_contract: EthAddress,
+    pub(crate) lock_duration: LockDuration,
+    pub(crate) eth_block_time: Duration,
+    pub(crate) whitelist_tokens: UnorderedMap<AccountId, WhitelistMode>,
+    pub(crate) whitelist_accounts: UnorderedSet<String>,
+    pub(crate) is_whitelist_mode_enabled: bool,
+    pub(crate) pending_transfers_balances: UnorderedMap<AccountId, u128>,
+}
+
+impl From<FastBridgeV0> for FastBridge {
+    fn from(state: FastBridgeV0) -> Self {
+        let mut account_whitelist_tokens = LookupMap::new(StorageKey::AccountWhitelistTokens);
+        for key in state.whitelist_accounts.iter() {
+            // The global entries are keyed by the account only
+            if let Some((token, account)) = key.split_once(':') {
+                let account: AccountId = account.parse().unwrap();
+                let mut account_tokens = account_whitelist_tokens
+                    .get(&account)
+                    .unwrap_or_else(|| whitelist::new_account_whitelist_tokens(&account));
+                account_tokens.insert(&token.parse().unwrap());
+                account_whitelist_tokens.insert(&account, &account_tokens);
+            }
+        }
+
+        Self {
+            pending_transfers: state.pending_transfers,
+            token_balances: state.token_balances,
+            nonce: state.nonce,
+            prover_account: state.prover_account,
+            eth_client_account: state.eth_client_account,
+            eth_bridge_contract: state.eth_bridge_contract,
+            lock_duration: state.lock_duration,
+            eth_block_time: state.eth_block_time,
+            whitelist_tokens: state.whitelist_tokens,
+            whitelist_accounts: state.whitelist_accounts,
+            is_whitelist_mode_enabled: state.is_whitelist_mode_enabled,
+            pending_transfers_balances: state.pending_transfers_balances,
+            pending_transfers_details: LookupMap::new(StorageKey::PendingTransfersDetails),
+            transfer_storage_balances: LookupMap::new(StorageKey::TransferStorageBalances),
+            transfer_storage_bonds: LookupMap::new(StorageKey::TransferStorageBonds),
+            blocked_accounts: UnorderedSet::new(StorageKey::BlockedAccounts),
+            eth_address_denylist: UnorderedSet::new(StorageKey::EthAddressDenylist),
+            token_pairs: UnorderedMap::new(StorageKey::TokenPairs),
+            is_token_pair_check_enabled: false,
+            transfer_reservations: LookupMap::new(StorageKey::TransferReservations),
+            transfer_reservation_config: None,
+            registered_lps: UnorderedMap::new(StorageKey::RegisteredLps),
+            lp_eth_relayers: LookupMap::new(StorageKey::LpEthRelayers),
+            lp_stake_tokens: UnorderedMap::new(StorageKey::LpStakeTokens),
+            is_lp_registry_enabled: false,
+            aurora_config: None,
+            proof_verifier: proof_verifier::ProofVerifier::RainbowProver,
+            token_proof_verifiers: UnorderedMap::new(StorageKey::TokenProofVerifiers),
+            committee: None,
+            optimistic_claims: LookupMap::new(StorageKey::OptimisticClaims),
+            optimistic_claim_config: None,
+            quotes: UnorderedMap::new(StorageKey::Quotes),
+            quote_nonce: 0,
+            dex_account: None,
+            pending_native_fee_balance: 0,
+            max_integrator_fee_bps: 0,
+            integrator_stats: LookupMap::new(StorageKey::IntegratorStats),
+            lp_unbondings: LookupMap::new(StorageKey::LpUnbondings),
+            token_quotes: LookupMap::new(StorageKey::TokenQuotes),
+            lp_quotes: LookupMap::new(StorageKey::LpQuotes),
+            account_whitelist_tokens,
+            native_fee_balances: LookupMap::new(StorageKey::NativeFeeBalances),
+            swap_nonce: 0,
+            swap_receipts: LookupMap::new(StorageKey::SwapReceipts),
+            eth_relayer_nonces: LookupMap::new(StorageKey::EthRelayerNonces),
+        }
+    }
+}
+
+#[near_bindgen]
+impl FastBridge {
+    /// Migrates the `FastBridgeV0` state of the deployed contract to the current layout. Should be called
+    /// in the same transaction as the code upgrade, the pending transfers keep working without their details.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let state: FastBridgeV0 = env::state_read()
+            .unwrap_or_else(|| env::panic_str("The contract state isn't initialized"));
+        state.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn test_migrate_v0_state() {
+        let current_account_id: AccountId = "bridge.near".parse().unwrap();
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(current_account_id.clone())
+            .predecessor_account_id(current_account_id)
+            .build());
+
+        let mut whitelist_tokens = UnorderedMap::new(StorageKey::WhitelistTokens);
+        whitelist_tokens.insert(&"token.near".parse().unwrap(), &WhitelistMode::Blocked);
+        let mut whitelist_accounts = UnorderedSet::new(StorageKey::WhitelistAccounts);
+        whitelist_accounts.insert(&"token.near:alice.near".to_string());
+        whitelist_accounts.insert(&"bob.near".to_string());
+        env::state_write(&FastBridgeV0 {
+            pending_transfers: UnorderedMap::new(StorageKey::PendingTransfers),
+            token_balances: LookupMap::new(StorageKey::TokenBalances),
+            nonce: 7,
+            prover_account: "prover.near".parse().unwrap(),
+            eth_client_account: "client.near".parse().unwrap(),
+            eth_bridge_contract: get_eth_address(
+                "6b175474e89094c44da98b954eedeac495271d0f".to_string(),
+            ),
+            lock_duration: LockDuration {
+                lock_time_min: 1,
+                lock_time_max: 2,
+            },
+            eth_block_time: 12,
+            whitelist_tokens,
+            whitelist_accounts,
+            is_whitelist_mode_enabled: true,
+            pending_transfers_balances: UnorderedMap::new(StorageKey::PendingTransfersBalances),
+        });
+
+        let contract = FastBridge::migrate();
+        assert_eq!(contract.nonce, 7);
+        assert_eq!(contract.eth_block_time, 12);
+        assert!(contract.is_whitelist_mode_enabled);
+        assert_eq!(
+            contract
+                .whitelist_tokens
+                .get(&"token.near".parse().unwrap()),
+            Some(WhitelistMode::Blocked)
+        );
+        assert_eq!(
+            contract.get_whitelisted_tokens_for_account("alice.near".parse().unwrap(), 0, 10),
+            vec!["token.near".parse::<AccountId>().unwrap()]
+        );
+        assert!(contract
+            .get_whitelisted_tokens_for_account("bob.near".parse().unwrap(), 0, 10)
+            .is_empty());
+        assert_eq!(contract.max_integrator_fee_bps, 0);
+        assert!(contract.committee.is_none());
+    }
+}
@@ -0,0 +1,122 @@
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, AccountId, Promise};
+
+use crate::*;
+
+#[near_bindgen]
+impl FastBridge {
+    /// Gets the total native NEAR fee locked by the pending transfers.
+    pub fn get_pending_native_fee_balance(&self) -> U128 {
+        U128(self.pending_native_fee_balance)
+    }
+
+    /// Gets the native NEAR fee credited to the LP `account_id` by the filled transfers.
+    pub fn get_native_fee_balance(&self, account_id: AccountId) -> U128 {
+        U128(self.native_fee_balances.get(&account_id).unwrap_or(0))
+    }
+
+    /// Withdraws the specified `amount` of the native NEAR fee credited to the caller.
+    ///
+    /// Requires an attached deposit of exactly 1 yoctoNEAR.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount to withdraw. If `None` is provided, the entire balance of the caller will be withdrawn.
+    #[payable]
+    pub fn withdraw_native_fee(&mut self, amount: Option<U128>) -> Promise {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let balance = self.get_native_fee_balance(account_id.clone());
+        let amount = amount.unwrap_or(balance);
+
+        require!(amount.0 > 0, "The amount should be a positive number");
+        require!(amount <= balance, "Insufficient native fee balance");
+
+        if balance == amount {
+            self.native_fee_balances.remove(&account_id);
+        } else {
+            self.native_fee_balances
+                .insert(&account_id, &(balance.0 - amount.0));
+        }
+
+        FastBridgeEvent::FastBridgeWithdrawNativeFeeEvent {
+            account_id: account_id.clone(),
+            amount,
+        }
+        .emit();
+
+        Promise::new(account_id).transfer(amount.0)
+    }
+}
+
+impl FastBridge {
+    /// Moves the native NEAR fee of the new transfer from the storage balance of the `sender_id`.
+    /// The deposit attached to `init_transfer()` is added to that balance beforehand.
+    pub(crate) fn lock_native_fee(&mut self, sender_id: &AccountId, native_fee_amount: u128) {
+        let balance = self.get_transfer_storage_balance(sender_id).0;
+        require!(
+            balance >= native_fee_amount,
+            format!(
+                "Not enough NEAR for the native fee, required: {}, available: {}",
+                native_fee_amount, balance
+            )
+        );
+
+        self.transfer_storage_balances
+            .insert(sender_id, &(balance - native_fee_amount));
+        self.pending_native_fee_balance += native_fee_amount;
+    }
+
+    /// Credits the native NEAR fee of the filled transfer to the native fee balance of the LP, or refunds it
+    /// to the storage balance of the `originator_id` if the LP isn't eligible for the fees.
+    pub(crate) fn pay_native_fee(
+        &mut self,
+        transfer_id: &String,
+        recipient_id: &AccountId,
+        originator_id: &AccountId,
+        eth_relayer: &EthAddress,
+    ) {
+        if self.is_eligible_for_lp_fee(recipient_id, eth_relayer) {
+            self.release_native_fee(transfer_id, recipient_id, false);
+        } else {
+            self.release_native_fee(transfer_id, originator_id, true);
+        }
+    }
+
+    /// Refunds the native NEAR fee of the unlocked transfer to the storage balance of the `recipient_id`.
+    pub(crate) fn refund_native_fee(&mut self, transfer_id: &String, recipient_id: &AccountId) {
+        self.release_native_fee(transfer_id, recipient_id, true);
+    }
+
+    fn release_native_fee(
+        &mut self,
+        transfer_id: &String,
+        recipient_id: &AccountId,
+        is_refund: bool,
+    ) {
+        let Some(native_fee_amount) = self
+            .pending_transfers_details
+            .get(transfer_id)
+            .and_then(|transfer_details| transfer_details.native_fee_amount)
+        else {
+            return;
+        };
+
+        self.pending_native_fee_balance -= native_fee_amount.0;
+        if is_refund {
+            self.increase_transfer_storage_balance(recipient_id, native_fee_amount.0);
+        } else {
+            let balance = self.get_native_fee_balance(recipient_id.clone()).0;
+            self.native_fee_balances
+                .insert(recipient_id, &(balance + native_fee_amount.0));
+        }
+
+        FastBridgeEvent::FastBridgeReleaseNativeFeeEvent {
+            nonce: U128(transfer_id.parse().unwrap()),
+            recipient_id: recipient_id.clone(),
+            amount: native_fee_amount,
+            is_refund,
+        }
+        .emit();
+    }
+}
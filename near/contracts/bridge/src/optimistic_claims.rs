@@ -0,0 +1,393 @@
+use near_plugins::access_control_any;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId, Duration, Promise};
+
+use crate::*;
+
+/// The settings of the optimistic LP claims.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OptimisticClaimConfig {
+    /// The minimal NEAR bond that should be attached to the claim
+    pub min_bond: U128,
+    /// The bond in basis points of the transfer amount that is locked from the LP balance of the transfer token
+    pub bond_bps: u32,
+    /// The duration of the window to challenge the claim, in nanoseconds
+    pub challenge_period: Duration,
+}
+
+/// The claim of the LP that the transfer was filled on Ethereum, which isn't backed by a proof.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OptimisticClaim {
+    /// The NEAR account that posted the bond
+    pub lp_account: AccountId,
+    /// The Ethereum relayer that filled the transfer
+    pub eth_relayer: EthAddress,
+    pub unlock_recipient: AccountId,
+    /// The NEAR bond attached to the claim
+    pub bond: U128,
+    /// The token of the bond that scales with the transfer amount
+    pub bond_token: AccountId,
+    /// The bond locked from the LP balance of the `bond_token`
+    pub token_bond: U128,
+    /// The Ethereum block height of the fill named by the LP, it's the minimal block height
+    /// of the storage proofs that challenge the claim
+    pub eth_block_height: u64,
+    /// The timestamp when the challenge window ends
+    pub challenge_till: u64,
+}
+
+#[near_bindgen]
+impl FastBridge {
+    /// Claims that the `eth_relayer` filled the pending transfer with the given `nonce`, without a proof.
+    /// The transfer is credited to the `unlock_recipient` with `finalize_optimistic_claim()` once the challenge
+    /// window ends. The attached deposit and the `bond_bps` of the transfer amount, locked from the balance
+    /// of the caller in the transfer token, are the bond of the claim. The bond is returned to the caller
+    /// on finalization.
+    ///
+    /// The bond is slashed if the claim is challenged:
+    /// * with `challenge_optimistic_claim()` by the proof that the transfer isn't processed on Ethereum
+    ///   at the `eth_block_height` of the fill named by the LP or at any later block;
+    /// * with `lp_unlock()` by the proof of the fill made by another relayer or to another recipient,
+    ///   the bond is paid to the recipient of the proven fill;
+    /// * with `unlock()` by the proof that the transfer isn't processed till its `valid_till_block_height`,
+    ///   the bond is paid to the originator of the transfer.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - The nonce of the pending transfer.
+    /// * `eth_relayer` - The hex-encoded address of the relayer that filled the transfer on Ethereum.
+    /// * `unlock_recipient` - The account that receives the unlocked tokens.
+    /// * `eth_block_height` - The Ethereum block height of the fill.
+    #[payable]
+    #[pause(except(roles(Role::UnrestrictedLpUnlock)))]
+    pub fn lp_claim_optimistic(
+        &mut self,
+        nonce: U128,
+        eth_relayer: String,
+        unlock_recipient: AccountId,
+        eth_block_height: u64,
+    ) -> OptimisticClaim {
+        let config = self
+            .optimistic_claim_config
+            .clone()
+            .unwrap_or_else(|| env::panic_str("Optimistic claims are disabled"));
+        let bond = env::attached_deposit();
+        require!(
+            bond >= config.min_bond.0,
+            format!("The claim bond should be at least {}", config.min_bond.0)
+        );
+
+        let lp_account = env::predecessor_account_id();
+        self.check_account_not_blocked(&lp_account);
+        self.check_account_not_blocked(&unlock_recipient);
+
+        let transfer_id = nonce.0.to_string();
+        let (_, transfer_message) = self
+            .pending_transfers
+            .get(&transfer_id)
+            .unwrap_or_else(|| env::panic_str("Transfer not found"));
+        // The fill can't be ahead of the Ethereum chain, so the named block can be challenged in time
+        require!(
+            eth_block_height <= self.estimate_eth_block_height(&transfer_message),
+            "The fill block height is ahead of the Ethereum chain"
+        );
+        require!(
+            !self.optimistic_claims.contains_key(&transfer_id),
+            "The transfer is already claimed"
+        );
+        let is_partially_filled = self
+            .pending_transfers_details
+            .get(&transfer_id)
+            .and_then(|transfer_details| transfer_details.partial_fill)
+            .map_or(false, |partial_fill| partial_fill.filled_amount_eth.0 > 0);
+        require!(
+            !is_partially_filled,
+            "The transfer is already partially filled"
+        );
+
+        // The reservation is released only when the claim is finalized, so it can be slashed by a challenge
+        let eth_relayer = get_eth_address(eth_relayer);
+        self.check_transfer_reservation(&transfer_id, &eth_relayer, Some(eth_block_height));
+
+        let bond_token = transfer_message.transfer.token_near.clone();
+        let token_bond = utils::mul_div(
+            transfer_message.transfer.amount.0,
+            config.bond_bps.into(),
+            utils::MAX_FEE_BPS.into(),
+        );
+        if token_bond > 0 {
+            require!(
+                self.get_user_balance(&lp_account, &bond_token).0 >= token_bond,
+                format!(
+                    "The claim bond should include {} of the transfer token",
+                    token_bond
+                )
+            );
+            self.decrease_balance(&lp_account, &bond_token, &token_bond);
+        }
+
+        let claim = OptimisticClaim {
+            lp_account,
+            eth_relayer,
+            unlock_recipient,
+            bond: U128(bond),
+            bond_token,
+            token_bond: U128(token_bond),
+            eth_block_height,
+            challenge_till: block_timestamp() + config.challenge_period,
+        };
+        self.optimistic_claims.insert(&transfer_id, &claim);
+
+        FastBridgeEvent::FastBridgeOptimisticClaimEvent {
+            nonce,
+            claim: claim.clone(),
+        }
+        .emit();
+
+        claim
+    }
+
+    /// Credits the unchallenged optimistic claim of the transfer with the given `nonce` after its challenge window.
+    #[pause(except(roles(Role::UnrestrictedLpUnlock)))]
+    pub fn finalize_optimistic_claim(&mut self, nonce: U128) -> TransferMessage {
+        let transfer_id = nonce.0.to_string();
+        let claim = self
+            .optimistic_claims
+            .get(&transfer_id)
+            .unwrap_or_else(|| env::panic_str("The claim not found"));
+        require!(
+            block_timestamp() >= claim.challenge_till,
+            "The challenge window isn't over yet"
+        );
+
+        let (_, transfer_message) = self
+            .pending_transfers
+            .get(&transfer_id)
+            .unwrap_or_else(|| env::panic_str("Transfer not found"));
+        let amount_eth = self.get_pending_transfer_amount_eth(&transfer_id, &transfer_message);
+        let payload_hash = self.get_pending_transfer_payload_hash(&transfer_id);
+        let transfer_event = EthTransferEvent {
+            eth_bridge_contract: self.eth_bridge_contract,
+            nonce: nonce.0,
+            relayer: claim.eth_relayer,
+            token: transfer_message.transfer.token_eth,
+            recipient: transfer_message.recipient,
+            amount: amount_eth,
+            filled_amount: None,
+            unlock_recipient: claim.unlock_recipient.to_string(),
+            transfer_id: utils::get_transfer_id(
+                transfer_message.transfer.token_eth,
+                transfer_message.recipient,
+                eth_types::U256(nonce.0.into()),
+                eth_types::U256(amount_eth.into()),
+                payload_hash,
+            )
+            .try_into()
+            .unwrap(),
+            payload_hash,
+            eth_block_height: Some(claim.eth_block_height),
+        };
+
+        // The claim is settled like a proven fill that returns the bond to the LP
+        self.lp_unlock_verified(transfer_event)
+    }
+
+    /// Challenges the optimistic claim of the transfer with the given `nonce` by the `proof` that the transfer
+    /// isn't processed on Ethereum at the block height named by the claim or at any later block. The bond
+    /// of the successfully challenged claim is transferred to the caller and the transfer stays pending.
+    /// The reservation of the transfer is slashed to the originator of the transfer.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - The nonce of the claimed transfer.
+    /// * `proof` - A Base64-encoded `UnlockProof` of the empty `processedHashes` of the transfer.
+    pub fn challenge_optimistic_claim(
+        &mut self,
+        nonce: U128,
+        proof: near_sdk::json_types::Base64VecU8,
+    ) -> Promise {
+        let proof = UnlockProof::try_from_slice(&proof.0)
+            .unwrap_or_else(|_| env::panic_str("Invalid borsh format of the `UnlockProof`"));
+        let transfer_id = nonce.0.to_string();
+        let claim = self
+            .optimistic_claims
+            .get(&transfer_id)
+            .unwrap_or_else(|| env::panic_str("The claim not found"));
+        require!(
+            block_timestamp() < claim.challenge_till,
+            "The challenge window is over"
+        );
+
+        let (_, transfer_message) = self
+            .pending_transfers
+            .get(&transfer_id)
+            .unwrap_or_else(|| env::panic_str("Transfer not found"));
+        let amount_eth = self.get_pending_transfer_amount_eth(&transfer_id, &transfer_message);
+        let storage_key_hash = utils::get_eth_storage_key_hash(
+            transfer_message.transfer.token_eth,
+            transfer_message.recipient,
+            eth_types::U256(nonce.0.into()),
+            eth_types::U256(amount_eth.into()),
+            self.get_pending_transfer_payload_hash(&transfer_id),
+        );
+
+        self.verify_transfer_storage_proof(
+            &transfer_message.transfer.token_near,
+            proof,
+            storage_key_hash,
+            vec![],
+            Some(claim.eth_block_height),
+        )
+        .then(
+            ext_self::ext(current_account_id())
+                .with_static_gas(utils::tera_gas(10))
+                .with_attached_deposit(utils::NO_DEPOSIT)
+                .challenge_optimistic_claim_callback(nonce, claim, env::predecessor_account_id()),
+        )
+    }
+
+    #[private]
+    pub fn challenge_optimistic_claim_callback(
+        &mut self,
+        #[callback]
+        #[serializer(borsh)]
+        verification_result: bool,
+        #[serializer(borsh)] nonce: U128,
+        #[serializer(borsh)] claim: OptimisticClaim,
+        #[serializer(borsh)] challenger_id: AccountId,
+    ) {
+        require!(verification_result, "Failed to verify the proof");
+
+        let transfer_id = nonce.0.to_string();
+        require!(
+            self.optimistic_claims.get(&transfer_id) == Some(claim.clone()),
+            "The claim is already settled"
+        );
+        self.slash_optimistic_claim(&transfer_id, &challenger_id);
+
+        // The false claim forfeits the reservation of the transfer to its originator
+        if let Some((originator_id, _)) = self.pending_transfers.get(&transfer_id) {
+            self.release_transfer_reservation(&transfer_id, originator_id, true);
+        }
+    }
+
+    /// Sets the settings of the optimistic claims, `None` disables new claims.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn set_optimistic_claim_config(&mut self, config: Option<OptimisticClaimConfig>) {
+        if let Some(config) = config.as_ref() {
+            require!(
+                config.bond_bps <= utils::MAX_FEE_BPS,
+                format!("The bond can't exceed {} basis points", utils::MAX_FEE_BPS)
+            );
+        }
+        let previous_config = std::mem::replace(&mut self.optimistic_claim_config, config.clone());
+
+        FastBridgeEvent::FastBridgeSetOptimisticClaimConfigEvent {
+            previous_config,
+            config,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn get_optimistic_claim_config(&self) -> Option<OptimisticClaimConfig> {
+        self.optimistic_claim_config.clone()
+    }
+
+    pub fn get_optimistic_claim(&self, nonce: U128) -> Option<OptimisticClaim> {
+        self.optimistic_claims.get(&nonce.0.to_string())
+    }
+}
+
+impl FastBridge {
+    /// Settles the optimistic claim of the transfer by the proven `fill` to the `recipient_id`. The claim that
+    /// matches the fill returns the bond to the LP, otherwise the claim was false and its bond is slashed
+    /// to the `recipient_id` of the fill.
+    pub(crate) fn settle_optimistic_claim(
+        &mut self,
+        transfer_id: &String,
+        fill: &EthTransferEvent,
+        recipient_id: &AccountId,
+    ) {
+        let Some(claim) = self.optimistic_claims.get(transfer_id) else {
+            return;
+        };
+
+        if fill.filled_amount.is_none()
+            && fill.relayer == claim.eth_relayer
+            && *recipient_id == claim.unlock_recipient
+        {
+            self.release_optimistic_claim(transfer_id);
+        } else {
+            self.slash_optimistic_claim(transfer_id, recipient_id);
+        }
+    }
+
+    /// Removes the optimistic claim of the transfer and returns the bond to the LP.
+    pub(crate) fn release_optimistic_claim(&mut self, transfer_id: &String) {
+        let Some(claim) = self.optimistic_claims.remove(transfer_id) else {
+            return;
+        };
+
+        self.transfer_optimistic_claim_bond(&claim, &claim.lp_account);
+
+        FastBridgeEvent::FastBridgeReleaseOptimisticClaimEvent {
+            nonce: U128(transfer_id.parse().unwrap()),
+            claim,
+        }
+        .emit();
+    }
+
+    /// Removes the optimistic claim of the transfer that is proven to be false and transfers the bond
+    /// to the `challenger_id`.
+    pub(crate) fn slash_optimistic_claim(
+        &mut self,
+        transfer_id: &String,
+        challenger_id: &AccountId,
+    ) {
+        let Some(claim) = self.optimistic_claims.remove(transfer_id) else {
+            return;
+        };
+
+        self.transfer_optimistic_claim_bond(&claim, challenger_id);
+
+        FastBridgeEvent::FastBridgeChallengeOptimisticClaimEvent {
+            nonce: U128(transfer_id.parse().unwrap()),
+            claim,
+            challenger_id: challenger_id.clone(),
+        }
+        .emit();
+    }
+
+    fn transfer_optimistic_claim_bond(
+        &mut self,
+        claim: &OptimisticClaim,
+        recipient_id: &AccountId,
+    ) {
+        if claim.bond.0 > 0 {
+            Promise::new(recipient_id.clone()).transfer(claim.bond.0);
+        }
+        if claim.token_bond.0 > 0 {
+            self.increase_balance(recipient_id, &claim.bond_token, &claim.token_bond.0);
+        }
+    }
+
+    /// Estimates the current Ethereum block height from the `valid_till_block_height` of the transfer.
+    /// The fills after the `valid_till_block_height` are rejected by Ethereum, so the estimate doesn't exceed it.
+    pub(crate) fn estimate_eth_block_height(&self, transfer_message: &TransferMessage) -> u64 {
+        let valid_till_block_height = transfer_message
+            .valid_till_block_height
+            .unwrap_or_else(|| env::panic_str("The transfer has no valid till block height"));
+        let remaining_blocks = transfer_message
+            .valid_till
+            .saturating_sub(block_timestamp())
+            / self.eth_block_time;
+
+        valid_till_block_height.saturating_sub(remaining_blocks)
+    }
+}
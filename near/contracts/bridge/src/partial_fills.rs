@@ -0,0 +1,142 @@
+use near_sdk::json_types::U128;
+use near_sdk::AccountId;
+
+use crate::*;
+
+/// The maximal number of the partial fills of a transfer, it matches `MAX_PARTIAL_FILLS` of the Ethereum bridge.
+/// Every fill but the last one should be at least `1 / MAX_PARTIAL_FILLS` of the transfer amount,
+/// so the claimed fills stored in the `PartialFill` are bounded.
+pub const MAX_PARTIAL_FILLS: u128 = 10;
+
+/// Gets the minimal amount of the partial fill that doesn't complete the transfer of the `amount_eth`.
+pub fn get_min_fill_amount(amount_eth: u128) -> u128 {
+    amount_eth / MAX_PARTIAL_FILLS + u128::from(amount_eth % MAX_PARTIAL_FILLS != 0)
+}
+
+impl FastBridge {
+    /// Credits the `recipient_id` with the part of the transfer amount and fee proportional to the
+    /// filled amount. The transfer is removed once it's filled completely, otherwise the remainder
+    /// stays pending and can be filled by other LPs or unlocked by the originator after the expiry.
+    ///
+    /// Returns the amount and the fee amount credited to the `recipient_id`.
+    pub(crate) fn unlock_partial_fill(
+        &mut self,
+        transfer_id: &String,
+        originator_id: &AccountId,
+        transfer_message: &TransferMessage,
+        proof: &EthTransferEvent,
+        filled_amount: u128,
+        recipient_id: AccountId,
+    ) -> (u128, u128) {
+        let mut transfer_details = self
+            .pending_transfers_details
+            .get(transfer_id)
+            .unwrap_or_else(|| env::panic_str("The transfer doesn't allow partial fills"));
+        let mut partial_fill = transfer_details
+            .partial_fill
+            .take()
+            .unwrap_or_else(|| env::panic_str("The transfer doesn't allow partial fills"));
+        let amount_eth = transfer_details.amount_eth.0;
+
+        let expected_transfer_id = utils::get_transfer_id(
+            transfer_message.transfer.token_eth,
+            transfer_message.recipient,
+            eth_types::U256(proof.nonce.into()),
+            eth_types::U256(amount_eth.into()),
+            None,
+        );
+        require!(
+            proof.transfer_id.0 .0.as_slice() == expected_transfer_id.as_slice(),
+            "Wrong transfer id of the partial fill"
+        );
+        require!(
+            proof.amount > 0 && proof.amount <= filled_amount && filled_amount <= amount_eth,
+            format!(
+                "Wrong partial fill {} with the filled amount {}, expected at most {}",
+                proof.amount, filled_amount, amount_eth
+            )
+        );
+        require!(
+            proof.amount >= get_min_fill_amount(amount_eth) || filled_amount == amount_eth,
+            format!(
+                "The partial fill should be at least {}",
+                get_min_fill_amount(amount_eth)
+            )
+        );
+        require!(
+            !partial_fill.claimed_fills.contains(&U128(filled_amount)),
+            "The partial fill is already claimed"
+        );
+
+        let filled_amount_eth = partial_fill.filled_amount_eth.0 + proof.amount;
+        require!(
+            filled_amount_eth <= amount_eth,
+            "The claimed fills exceed the transfer amount"
+        );
+
+        // The last fill takes the remainder, so the rounding never leaves tokens pending
+        let (amount, fee_amount) = if filled_amount_eth == amount_eth {
+            self.get_pending_transfer_remainder(transfer_id, transfer_message)
+        } else {
+            (
+                utils::mul_div(transfer_message.transfer.amount.0, proof.amount, amount_eth),
+                utils::mul_div(transfer_message.fee.amount.0, proof.amount, amount_eth),
+            )
+        };
+
+        self.increase_balance(
+            &recipient_id,
+            &transfer_message.transfer.token_near,
+            &amount,
+        );
+        let lp_fee_amount = self.apply_fee_schedule(
+            transfer_id,
+            originator_id,
+            transfer_message,
+            proof,
+            fee_amount,
+        );
+        let credited_fee_amount = self.credit_lp_fee(
+            U128(proof.nonce),
+            &recipient_id,
+            originator_id,
+            &proof.relayer,
+            &transfer_message.fee.token,
+            lp_fee_amount,
+        );
+
+        if filled_amount_eth == amount_eth {
+            self.remove_transfer(transfer_id, transfer_message);
+        } else {
+            let pending_balance = self
+                .pending_transfers_balances
+                .get(&transfer_message.transfer.token_near)
+                .unwrap_or_else(|| env::panic_str("Pending balance does not exist"));
+            self.pending_transfers_balances.insert(
+                &transfer_message.transfer.token_near,
+                &(pending_balance - amount),
+            );
+
+            partial_fill.filled_amount_eth = U128(filled_amount_eth);
+            partial_fill.unlocked_amount = U128(partial_fill.unlocked_amount.0 + amount);
+            partial_fill.unlocked_fee_amount =
+                U128(partial_fill.unlocked_fee_amount.0 + fee_amount);
+            partial_fill.claimed_fills.push(U128(filled_amount));
+            transfer_details.partial_fill = Some(partial_fill);
+            self.pending_transfers_details
+                .insert(transfer_id, &transfer_details);
+        }
+
+        FastBridgeEvent::FastBridgeLpPartialUnlockEvent {
+            nonce: U128(proof.nonce),
+            recipient_id,
+            amount: U128(amount),
+            fee_amount: U128(fee_amount),
+            filled_amount_eth: U128(filled_amount_eth),
+            transfer_message: transfer_message.clone(),
+        }
+        .emit();
+
+        (amount, credited_fee_amount)
+    }
+}
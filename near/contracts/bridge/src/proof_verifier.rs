@@ -0,0 +1,128 @@
+use near_plugins::access_control_any;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId, Promise};
+
+use crate::*;
+
+/// The backend that verifies the Ethereum proofs of the transfers.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProofVerifier {
+    /// The Rainbow Bridge `EthProver` set with `set_prover_account()`
+    RainbowProver,
+    /// The committee of Ethereum keys that attests the transfer events, see `lp_unlock_with_attestations()`.
+    /// The proofs are still verified by the Rainbow Bridge `EthProver` if the attestations are unavailable.
+    Committee,
+    /// The verifier of a ZK Ethereum light client that implements the `EthProver` interface
+    ZkLightClient { verifier_account: AccountId },
+}
+
+#[near_bindgen]
+impl FastBridge {
+    /// Sets the proof verifier of the `token`, or the default verifier of all tokens if the `token` is `None`.
+    /// The `None` verifier removes the verifier of the token or resets the default one to the Rainbow prover.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn set_proof_verifier(
+        &mut self,
+        token: Option<AccountId>,
+        verifier: Option<ProofVerifier>,
+    ) {
+        let previous_verifier = match (&token, verifier.clone()) {
+            (Some(token), Some(verifier)) => self.token_proof_verifiers.insert(token, &verifier),
+            (Some(token), None) => self.token_proof_verifiers.remove(token),
+            (None, verifier) => Some(std::mem::replace(
+                &mut self.proof_verifier,
+                verifier.unwrap_or(ProofVerifier::RainbowProver),
+            )),
+        };
+
+        FastBridgeEvent::FastBridgeSetProofVerifierEvent {
+            token,
+            previous_verifier,
+            verifier,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    /// Gets the proof verifier that is used for the `token`, or the default verifier if the `token` is `None`.
+    pub fn get_proof_verifier(&self, token: Option<AccountId>) -> ProofVerifier {
+        match token {
+            Some(token) => self.get_token_proof_verifier(&token),
+            None => self.proof_verifier.clone(),
+        }
+    }
+
+    /// Returns a page of the tokens that override the default proof verifier.
+    pub fn get_token_proof_verifiers(
+        &self,
+        from_index: usize,
+        limit: usize,
+    ) -> Vec<(AccountId, ProofVerifier)> {
+        self.token_proof_verifiers
+            .iter()
+            .skip(from_index)
+            .take(limit)
+            .collect::<Vec<_>>()
+    }
+}
+
+impl FastBridge {
+    pub(crate) fn get_token_proof_verifier(&self, token: &AccountId) -> ProofVerifier {
+        self.token_proof_verifiers
+            .get(token)
+            .unwrap_or_else(|| self.proof_verifier.clone())
+    }
+
+    /// Gets the `EthProver` that verifies the Ethereum proofs for the `token`.
+    fn get_prover_account(&self, token: &AccountId) -> AccountId {
+        match self.get_token_proof_verifier(token) {
+            ProofVerifier::RainbowProver | ProofVerifier::Committee => self.prover_account.clone(),
+            ProofVerifier::ZkLightClient { verifier_account } => verifier_account,
+        }
+    }
+
+    /// Verifies the proof of the transfer event of the `token` with its proof verifier.
+    pub(crate) fn verify_transfer_event_proof(&self, token: &AccountId, proof: Proof) -> Promise {
+        ext_prover::ext(self.get_prover_account(token))
+            .with_static_gas(utils::tera_gas(50))
+            .with_attached_deposit(utils::NO_DEPOSIT)
+            .verify_log_entry(
+                proof.log_index,
+                proof.log_entry_data,
+                proof.receipt_index,
+                proof.receipt_data,
+                proof.header_data,
+                proof.proof,
+                false,
+            )
+    }
+
+    /// Verifies the storage proof of the Ethereum bridge contract for the `token` with its proof verifier.
+    pub(crate) fn verify_transfer_storage_proof(
+        &self,
+        token: &AccountId,
+        proof: UnlockProof,
+        storage_key_hash: Vec<u8>,
+        expected_storage_value: Vec<u8>,
+        min_header_height: Option<u64>,
+    ) -> Promise {
+        ext_prover::ext(self.get_prover_account(token))
+            .with_static_gas(utils::tera_gas(50))
+            .with_attached_deposit(utils::NO_DEPOSIT)
+            .verify_storage_proof(
+                proof.header_data,
+                proof.account_proof,
+                self.eth_bridge_contract.0.to_vec(),
+                proof.account_data,
+                storage_key_hash,
+                proof.storage_proof,
+                expected_storage_value,
+                min_header_height,
+                None,
+                false,
+            )
+    }
+}
@@ -0,0 +1,370 @@
+use near_sdk::collections::UnorderedSet;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId};
+
+use crate::reservations::{get_reservation_end_block_height, TransferReservation};
+use crate::*;
+
+/// The fee that the registered LP offers for the transfers of the token.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Quote {
+    pub lp_account: AccountId,
+    /// The Ethereum relayer of the LP that fills the transfers bound to the quote
+    pub eth_relayer: EthAddress,
+    pub token: AccountId,
+    /// The maximal transfer amount, in the NEAR token decimals
+    pub max_amount: U128,
+    /// The total amount of the transfers that can still be bound to the quote, in the NEAR token decimals
+    pub remaining_capacity: U128,
+    /// The fee in basis points of the transfer amount, paid in the transfer token
+    pub fee_bps: u32,
+    /// The timestamp when the quote expires
+    pub expires_at: u64,
+    /// The duration of the exclusive fill window of the transfers bound to the quote, in Ethereum blocks
+    pub exclusivity_blocks: u64,
+    /// The NEAR locked from the transfer storage balance of the LP to cover the storage of the quote
+    pub storage_bond: U128,
+}
+
+impl Quote {
+    pub fn get_fee_amount(&self, amount: u128) -> u128 {
+        utils::mul_div(amount, self.fee_bps.into(), utils::MAX_FEE_BPS.into())
+    }
+
+    fn is_expired(&self) -> bool {
+        block_timestamp() >= self.expires_at
+    }
+
+    fn is_live_for(&self, token: &AccountId, amount: u128) -> bool {
+        self.token == *token
+            && amount <= self.max_amount.0
+            && amount <= self.remaining_capacity.0
+            && !self.is_expired()
+    }
+}
+
+#[near_bindgen]
+impl FastBridge {
+    /// Publishes the quote of the caller, the caller should be the registered LP that declared the `eth_relayer`.
+    /// The transfers initialized with the `quote_id` in the `TransferOptions` can be filled only by the
+    /// `eth_relayer` during the `exclusivity_blocks`.
+    ///
+    /// The storage of the quote is charged from the transfer storage balance of the caller, see
+    /// `transfer_storage_deposit()`, and is returned once the quote is removed. The expired quotes
+    /// of the `token` are removed by this call.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The NEAR token of the transfers.
+    /// * `eth_relayer` - The hex-encoded address of the relayer that fills the transfers on Ethereum.
+    /// * `max_amount` - The maximal transfer amount.
+    /// * `capacity` - The total amount of the transfers that can be bound to the quote, the quote is removed
+    ///   once it's used up.
+    /// * `fee_bps` - The fee in basis points of the transfer amount.
+    /// * `expires_at` - The timestamp when the quote expires.
+    /// * `exclusivity_blocks` - The duration of the exclusive fill window of the bound transfers, in Ethereum blocks.
+    ///
+    /// # Returns
+    ///
+    /// The id of the quote.
+    #[pause]
+    pub fn publish_quote(
+        &mut self,
+        token: AccountId,
+        eth_relayer: String,
+        max_amount: U128,
+        capacity: U128,
+        fee_bps: u32,
+        expires_at: u64,
+        exclusivity_blocks: u64,
+    ) -> U128 {
+        let lp_account = env::predecessor_account_id();
+        self.check_account_not_blocked(&lp_account);
+        let eth_relayer = get_eth_address(eth_relayer);
+        require!(
+            self.is_staked_lp_relayer(&lp_account, &eth_relayer),
+            "The relayer isn't declared by the registered LP"
+        );
+        require!(
+            fee_bps <= utils::MAX_FEE_BPS,
+            format!("The fee can't exceed {} basis points", utils::MAX_FEE_BPS)
+        );
+        require!(
+            capacity.0 > 0,
+            "The capacity of the quote should be positive"
+        );
+        require!(expires_at > block_timestamp(), "The quote is expired");
+        self.prune_expired_quotes(&token);
+
+        let initial_storage_usage = env::storage_usage();
+        let mut quote = Quote {
+            lp_account,
+            eth_relayer,
+            token,
+            max_amount,
+            remaining_capacity: capacity,
+            fee_bps,
+            expires_at,
+            exclusivity_blocks,
+            storage_bond: U128(0),
+        };
+        self.quote_nonce += 1;
+        let quote_id = U128(self.quote_nonce);
+        self.insert_quote(&quote_id, &quote);
+        quote.storage_bond = U128(self.lock_quote_storage_bond(&quote, initial_storage_usage));
+        self.quotes.insert(&quote_id.0.to_string(), &quote);
+
+        FastBridgeEvent::FastBridgePublishQuoteEvent { quote_id, quote }.emit();
+
+        quote_id
+    }
+
+    /// Removes the quote of the caller. The transfers that are already bound to the quote keep their
+    /// exclusive fill windows.
+    pub fn cancel_quote(&mut self, quote_id: U128) {
+        let quote = self.get_quote_or_panic(quote_id);
+        require!(
+            quote.lp_account == env::predecessor_account_id(),
+            "Only the LP of the quote can cancel it"
+        );
+        self.remove_quote(&quote_id, &quote);
+
+        FastBridgeEvent::FastBridgeCancelQuoteEvent { quote_id, quote }.emit();
+    }
+
+    pub fn get_quote(&self, quote_id: U128) -> Option<Quote> {
+        self.quotes.get(&quote_id.0.to_string())
+    }
+
+    /// Returns a page of the published quotes, including the expired ones that weren't removed yet.
+    pub fn get_quotes(&self, from_index: usize, limit: usize) -> Vec<(U128, Quote)> {
+        self.quotes
+            .iter()
+            .skip(from_index)
+            .take(limit)
+            .map(|(quote_id, quote)| (U128(quote_id.parse().unwrap()), quote))
+            .collect::<Vec<_>>()
+    }
+
+    /// Gets the live quote of a registered LP with the lowest fee for the transfer of the `amount`
+    /// of the `token_near` among a page of the quotes of the token.
+    pub fn get_best_quote(
+        &self,
+        token_near: AccountId,
+        amount: U128,
+        from_index: usize,
+        limit: usize,
+    ) -> Option<(U128, Quote)> {
+        self.token_quotes
+            .get(&token_near)?
+            .iter()
+            .skip(from_index)
+            .take(limit)
+            .filter_map(|quote_id| {
+                self.quotes
+                    .get(&quote_id)
+                    .map(|quote| (U128(quote_id.parse().unwrap()), quote))
+            })
+            .filter(|(_, quote)| {
+                quote.is_live_for(&token_near, amount.0) && self.is_quote_lp_eligible(quote)
+            })
+            .min_by_key(|(_, quote)| quote.fee_bps)
+    }
+}
+
+impl FastBridge {
+    /// Binds the new transfer to the quote by reserving the exclusive fill window for the relayer of the quote.
+    /// The window starts at the `last_block_height` of the Ethereum light client at the initialization.
+    /// The transfer should be in the token of the quote and pay at least the quoted fee in the transfer token,
+    /// the LP of the quote should still be registered with at least the minimal stake.
+    /// The transfer amount is taken from the remaining capacity of the quote.
+    pub(crate) fn bind_transfer_to_quote(
+        &mut self,
+        nonce: U128,
+        quote_id: U128,
+        transfer_message: &TransferMessage,
+        last_block_height: u64,
+    ) {
+        let mut quote = self.get_quote_or_panic(quote_id);
+        let amount = transfer_message.transfer.amount.0;
+        require!(
+            amount <= quote.remaining_capacity.0,
+            format!(
+                "The remaining capacity of the quote is {}",
+                quote.remaining_capacity.0
+            )
+        );
+        require!(
+            quote.is_live_for(&transfer_message.transfer.token_near, amount),
+            "The quote isn't valid for the transfer"
+        );
+        require!(
+            transfer_message.fee.token == quote.token
+                && transfer_message.fee.amount.0 >= quote.get_fee_amount(amount),
+            format!(
+                "The fee should be at least {} of the transfer token",
+                quote.get_fee_amount(amount)
+            )
+        );
+        self.check_account_not_blocked(&quote.lp_account);
+        require!(
+            self.is_staked_lp_relayer(&quote.lp_account, &quote.eth_relayer),
+            "The LP of the quote isn't registered with the minimal stake"
+        );
+        self.prune_expired_quotes(&quote.token);
+
+        quote.remaining_capacity = U128(quote.remaining_capacity.0 - amount);
+        if quote.remaining_capacity.0 == 0 {
+            self.remove_quote(&quote_id, &quote);
+            FastBridgeEvent::FastBridgeRemoveQuoteEvent {
+                quote_id,
+                quote: quote.clone(),
+            }
+            .emit();
+        } else {
+            self.quotes.insert(&quote_id.0.to_string(), &quote);
+        }
+
+        // The reservation of the quote has no bond, the LP is bound by its registry stake instead
+        let reservation = TransferReservation {
+            lp_account: quote.lp_account.clone(),
+            eth_relayer: quote.eth_relayer,
+            bond: U128(0),
+            valid_till_block_height: get_reservation_end_block_height(
+                transfer_message,
+                last_block_height,
+                quote.exclusivity_blocks,
+            ),
+        };
+        self.transfer_reservations
+            .insert(&nonce.0.to_string(), &reservation);
+
+        FastBridgeEvent::FastBridgeBindTransferToQuoteEvent {
+            nonce,
+            quote_id,
+            reservation,
+            remaining_capacity: quote.remaining_capacity,
+        }
+        .emit();
+    }
+
+    /// Removes all quotes of the `lp_account`, e.g. when the LP is unregistered.
+    pub(crate) fn remove_lp_quotes(&mut self, lp_account: &AccountId) {
+        let Some(quote_ids) = self.lp_quotes.get(lp_account) else {
+            return;
+        };
+
+        for quote_id in quote_ids.to_vec() {
+            let quote_id = U128(quote_id.parse().unwrap());
+            if let Some(quote) = self.get_quote(quote_id) {
+                self.remove_quote(&quote_id, &quote);
+                FastBridgeEvent::FastBridgeRemoveQuoteEvent { quote_id, quote }.emit();
+            }
+        }
+    }
+
+    /// Removes the expired quotes of the `token` and returns their storage bonds to the LPs.
+    fn prune_expired_quotes(&mut self, token: &AccountId) {
+        let Some(quote_ids) = self.token_quotes.get(token) else {
+            return;
+        };
+
+        for quote_id in quote_ids.to_vec() {
+            let quote_id = U128(quote_id.parse().unwrap());
+            let Some(quote) = self.get_quote(quote_id) else {
+                continue;
+            };
+            if quote.is_expired() {
+                self.remove_quote(&quote_id, &quote);
+                FastBridgeEvent::FastBridgeRemoveQuoteEvent { quote_id, quote }.emit();
+            }
+        }
+    }
+
+    /// Checks whether the LP of the quote may get the transfers bound to the quote.
+    fn is_quote_lp_eligible(&self, quote: &Quote) -> bool {
+        !self.blocked_accounts.contains(&quote.lp_account)
+            && self.is_staked_lp_relayer(&quote.lp_account, &quote.eth_relayer)
+    }
+
+    fn insert_quote(&mut self, quote_id: &U128, quote: &Quote) {
+        let quote_id = quote_id.0.to_string();
+        self.quotes.insert(&quote_id, quote);
+
+        let mut token_quote_ids = self
+            .token_quotes
+            .get(&quote.token)
+            .unwrap_or_else(|| new_quote_ids(StorageKey::TokenQuotesPrefix, &quote.token));
+        token_quote_ids.insert(&quote_id);
+        self.token_quotes.insert(&quote.token, &token_quote_ids);
+
+        let mut lp_quote_ids = self
+            .lp_quotes
+            .get(&quote.lp_account)
+            .unwrap_or_else(|| new_quote_ids(StorageKey::LpQuotesPrefix, &quote.lp_account));
+        lp_quote_ids.insert(&quote_id);
+        self.lp_quotes.insert(&quote.lp_account, &lp_quote_ids);
+    }
+
+    fn remove_quote(&mut self, quote_id: &U128, quote: &Quote) {
+        let quote_id = quote_id.0.to_string();
+        self.quotes.remove(&quote_id);
+
+        if let Some(mut token_quote_ids) = self.token_quotes.get(&quote.token) {
+            token_quote_ids.remove(&quote_id);
+            if token_quote_ids.is_empty() {
+                self.token_quotes.remove(&quote.token);
+            } else {
+                self.token_quotes.insert(&quote.token, &token_quote_ids);
+            }
+        }
+
+        if let Some(mut lp_quote_ids) = self.lp_quotes.get(&quote.lp_account) {
+            lp_quote_ids.remove(&quote_id);
+            if lp_quote_ids.is_empty() {
+                self.lp_quotes.remove(&quote.lp_account);
+            } else {
+                self.lp_quotes.insert(&quote.lp_account, &lp_quote_ids);
+            }
+        }
+
+        self.increase_transfer_storage_balance(&quote.lp_account, quote.storage_bond.0);
+    }
+
+    /// Charges the LP of the quote for the storage used since `initial_storage_usage`.
+    fn lock_quote_storage_bond(&mut self, quote: &Quote, initial_storage_usage: u64) -> u128 {
+        let used_bytes = env::storage_usage().saturating_sub(initial_storage_usage);
+        let bond = env::storage_byte_cost() * u128::from(used_bytes);
+        let balance = self.get_transfer_storage_balance(&quote.lp_account).0;
+
+        require!(
+            balance >= bond,
+            format!(
+                "Not enough storage balance to store the quote, required: {}, available: {}",
+                bond, balance
+            )
+        );
+
+        self.transfer_storage_balances
+            .insert(&quote.lp_account, &(balance - bond));
+        bond
+    }
+
+    fn get_quote_or_panic(&self, quote_id: U128) -> Quote {
+        self.quotes
+            .get(&quote_id.0.to_string())
+            .unwrap_or_else(|| env::panic_str("The quote not found"))
+    }
+}
+
+fn new_quote_ids(prefix: StorageKey, account_id: &AccountId) -> UnorderedSet<String> {
+    UnorderedSet::new(
+        [
+            prefix.try_to_vec().unwrap().as_slice(),
+            account_id.try_to_vec().unwrap().as_slice(),
+        ]
+        .concat(),
+    )
+}
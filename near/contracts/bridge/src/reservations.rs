@@ -0,0 +1,223 @@
+use near_plugins::access_control_any;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId, Promise};
+
+use crate::*;
+
+/// The settings of the exclusive fill windows that LPs may reserve for pending transfers.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReservationConfig {
+    /// The minimal NEAR bond that should be attached to the reservation
+    pub min_bond: U128,
+    /// The duration of the exclusive fill window, in Ethereum blocks
+    pub duration_blocks: u64,
+}
+
+/// The exclusive right of the LP to fill the transfer on Ethereum.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferReservation {
+    /// The NEAR account that posted the bond
+    pub lp_account: AccountId,
+    /// The Ethereum relayer that is expected to fill the transfer
+    pub eth_relayer: EthAddress,
+    pub bond: U128,
+    /// The Ethereum block height when the exclusive fill window ends
+    pub valid_till_block_height: u64,
+}
+
+/// Gets the Ethereum block height when the exclusive fill window that starts at the `start_block_height`
+/// ends, the window doesn't last beyond the `valid_till_block_height` of the transfer.
+pub fn get_reservation_end_block_height(
+    transfer_message: &TransferMessage,
+    start_block_height: u64,
+    duration_blocks: u64,
+) -> u64 {
+    let valid_till_block_height = transfer_message
+        .valid_till_block_height
+        .unwrap_or_else(|| env::panic_str("The transfer has no valid till block height"));
+    std::cmp::min(
+        start_block_height.saturating_add(duration_blocks),
+        valid_till_block_height,
+    )
+}
+
+#[near_bindgen]
+impl FastBridge {
+    /// Reserves the exclusive window to fill the pending transfer with the given `nonce`. During the window
+    /// only the fill made by the `eth_relayer` can be claimed with `lp_unlock`.
+    ///
+    /// The window starts at the Ethereum block height estimated at the reservation and can't last
+    /// beyond the `valid_till_block_height` of the transfer.
+    ///
+    /// The attached deposit is the bond of the reservation. The bond is returned to the caller once the fill
+    /// of the `eth_relayer` is claimed, otherwise it's slashed to the originator of the transfer when the transfer
+    /// is settled by another relayer or unlocked.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - The nonce of the pending transfer.
+    /// * `eth_relayer` - The hex-encoded address of the relayer that will fill the transfer on Ethereum.
+    #[payable]
+    #[pause]
+    pub fn reserve_transfer(&mut self, nonce: U128, eth_relayer: String) -> TransferReservation {
+        let config = self
+            .transfer_reservation_config
+            .clone()
+            .unwrap_or_else(|| env::panic_str("Transfer reservations are disabled"));
+        let bond = env::attached_deposit();
+        require!(bond > 0, "The reservation should be backed by a bond");
+        require!(
+            bond >= config.min_bond.0,
+            format!(
+                "The reservation bond should be at least {}",
+                config.min_bond.0
+            )
+        );
+
+        let lp_account = env::predecessor_account_id();
+        self.check_account_not_blocked(&lp_account);
+
+        let transfer_id = nonce.0.to_string();
+        let (_, transfer_message) = self
+            .pending_transfers
+            .get(&transfer_id)
+            .unwrap_or_else(|| env::panic_str("Transfer not found"));
+        require!(
+            block_timestamp() < transfer_message.valid_till,
+            "The transfer is expired"
+        );
+        require!(
+            !self.transfer_reservations.contains_key(&transfer_id),
+            "The transfer is already reserved"
+        );
+
+        let reservation = TransferReservation {
+            lp_account,
+            eth_relayer: get_eth_address(eth_relayer),
+            bond: U128(bond),
+            valid_till_block_height: get_reservation_end_block_height(
+                &transfer_message,
+                self.estimate_eth_block_height(&transfer_message),
+                config.duration_blocks,
+            ),
+        };
+        self.transfer_reservations
+            .insert(&transfer_id, &reservation);
+
+        FastBridgeEvent::FastBridgeReserveTransferEvent {
+            nonce,
+            reservation: reservation.clone(),
+        }
+        .emit();
+
+        reservation
+    }
+
+    /// Sets the settings of the transfer reservations, `None` disables new reservations.
+    /// The minimal bond should be positive, so every reservation that isn't bound to a quote can be slashed.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn set_transfer_reservation_config(&mut self, config: Option<ReservationConfig>) {
+        if let Some(config) = config.as_ref() {
+            require!(
+                config.min_bond.0 > 0,
+                "The minimal reservation bond should be positive"
+            );
+        }
+        let previous_config =
+            std::mem::replace(&mut self.transfer_reservation_config, config.clone());
+
+        FastBridgeEvent::FastBridgeSetTransferReservationConfigEvent {
+            previous_config,
+            config,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn get_transfer_reservation_config(&self) -> Option<ReservationConfig> {
+        self.transfer_reservation_config.clone()
+    }
+
+    pub fn get_transfer_reservation(&self, nonce: U128) -> Option<TransferReservation> {
+        self.transfer_reservations.get(&nonce.0.to_string())
+    }
+}
+
+impl FastBridge {
+    /// Checks that the fill of the `eth_relayer` at the `eth_block_height` can be claimed and releases
+    /// the reservation of the reserving relayer, which returns the bond to the LP.
+    pub(crate) fn use_transfer_reservation(
+        &mut self,
+        transfer_id: &String,
+        eth_relayer: &EthAddress,
+        eth_block_height: Option<u64>,
+    ) {
+        self.check_transfer_reservation(transfer_id, eth_relayer, eth_block_height);
+
+        if let Some(reservation) = self.transfer_reservations.get(transfer_id) {
+            if reservation.eth_relayer == *eth_relayer {
+                self.release_transfer_reservation(transfer_id, reservation.lp_account, false);
+            }
+        }
+    }
+
+    /// Checks that the fill of the `eth_relayer` at the `eth_block_height` can be claimed, the reservation
+    /// is kept. The fills of the relayers other than the reserving one are accepted only at or after
+    /// the end of the window, so their block height should be proven.
+    pub(crate) fn check_transfer_reservation(
+        &self,
+        transfer_id: &String,
+        eth_relayer: &EthAddress,
+        eth_block_height: Option<u64>,
+    ) {
+        let Some(reservation) = self.transfer_reservations.get(transfer_id) else {
+            return;
+        };
+
+        if reservation.eth_relayer != *eth_relayer {
+            let eth_block_height = eth_block_height.unwrap_or_else(|| {
+                env::panic_str("The block height of the fill of the reserved transfer is unknown")
+            });
+            require!(
+                eth_block_height >= reservation.valid_till_block_height,
+                format!(
+                    "The transfer is reserved by the relayer `{}`",
+                    hex::encode(reservation.eth_relayer.0)
+                )
+            );
+        }
+    }
+
+    /// Removes the reservation of the transfer and transfers its bond to the `recipient_id`.
+    /// The reservation without a bond is backed by the registry stake of the LP, so the stake is slashed instead.
+    pub(crate) fn release_transfer_reservation(
+        &mut self,
+        transfer_id: &String,
+        recipient_id: AccountId,
+        is_slashed: bool,
+    ) {
+        let Some(reservation) = self.transfer_reservations.remove(transfer_id) else {
+            return;
+        };
+
+        let nonce = U128(transfer_id.parse().unwrap());
+        if reservation.bond.0 > 0 {
+            Promise::new(recipient_id.clone()).transfer(reservation.bond.0);
+        } else if is_slashed {
+            self.slash_lp_stake(nonce, &reservation.lp_account, &recipient_id);
+        }
+
+        FastBridgeEvent::FastBridgeReleaseTransferReservationEvent {
+            nonce,
+            reservation,
+            recipient_id,
+            is_slashed,
+        }
+        .emit();
+    }
+}
@@ -0,0 +1,310 @@
+use near_plugins::access_control_any;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{base64, env, ext_contract, serde_json, AccountId, Promise};
+
+use crate::*;
+
+/// The prefix of the `ft_transfer_call` message that swaps the transferred tokens before the transfer.
+pub const SWAP_MESSAGE_PREFIX: &str = "swap:";
+
+/// The transfer that is initialized with the output of the swap, see `ft_on_transfer()`.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SwapAndTransferMessage {
+    /// The minimal output of the swap to the `token_near` of the transfer
+    pub min_amount_out: U128,
+    pub transfer_message: TransferMessage,
+    pub transfer_options: TransferOptions,
+}
+
+/// The `ft_transfer_call` message of the DEX that swaps the transferred tokens.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct SwapRequest {
+    token_out: AccountId,
+    min_amount_out: U128,
+    receipt_id: U128,
+}
+
+/// The swap output that is kept on the deposit of this contract on the DEX until it's withdrawn.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapReceipt {
+    pub sender_id: AccountId,
+    pub dex_account: AccountId,
+    pub token_in: AccountId,
+    pub amount_in: U128,
+    pub token_out: AccountId,
+    /// The output of the swap recorded by the DEX
+    pub amount_out: U128,
+}
+
+/// The DEX that swaps the tokens received with `ft_transfer_call`, keeps the output on the deposit
+/// of the sender and records it with the `receipt_id` of the swap request. All tokens are returned
+/// by `ft_transfer_call` if the swap fails.
+#[ext_contract(ext_dex)]
+trait Dex {
+    /// Gets the output of the swap with the `receipt_id` of the `account_id`, zero if there is no such swap.
+    fn get_swap_receipt(&self, account_id: AccountId, receipt_id: U128) -> U128;
+    /// Transfers the `amount` of the `token_id` from the deposit to the caller and returns the transferred amount.
+    fn withdraw(&mut self, token_id: AccountId, amount: U128) -> U128;
+}
+
+#[near_bindgen]
+impl FastBridge {
+    #[private]
+    pub fn swap_callback(
+        &mut self,
+        #[serializer(borsh)] sender_id: AccountId,
+        #[serializer(borsh)] token_in: AccountId,
+        #[serializer(borsh)] amount_in: U128,
+        #[serializer(borsh)] dex_account: AccountId,
+        #[serializer(borsh)] receipt_id: U128,
+        #[serializer(borsh)] swap_message: SwapAndTransferMessage,
+    ) -> PromiseOrValue<U128> {
+        let used_amount = promise_result_as_success()
+            .and_then(|value| serde_json::from_slice::<U128>(&value).ok())
+            .map_or(0, |used_amount| used_amount.0);
+        if used_amount < amount_in.0 {
+            FastBridgeEvent::FastBridgeSwapFailedEvent {
+                sender_id,
+                token_in,
+                amount_in,
+                refund_amount: U128(amount_in.0 - used_amount),
+            }
+            .emit();
+
+            // The unused amount is returned to the sender by the `ft_transfer_call` of the input token
+            return PromiseOrValue::Value(U128(amount_in.0 - used_amount));
+        }
+
+        let receipt = SwapReceipt {
+            sender_id,
+            dex_account,
+            token_in,
+            amount_in,
+            token_out: swap_message.transfer_message.transfer.token_near.clone(),
+            amount_out: U128(0),
+        };
+        self.read_swap_receipt(receipt_id, receipt, Some(swap_message))
+            .into()
+    }
+
+    /// Withdraws the output of the swap recorded by the DEX with the `receipt_id`. The `amount_out`
+    /// of the `receipt` is replaced with the recorded output. The receipt is stored in this contract
+    /// to be withdrawn with `withdraw_swap_receipt()` if the output can't be read.
+    #[private]
+    pub fn swap_receipt_callback(
+        &mut self,
+        #[serializer(borsh)] receipt_id: U128,
+        #[serializer(borsh)] receipt: SwapReceipt,
+        #[serializer(borsh)] swap_message: Option<SwapAndTransferMessage>,
+    ) -> PromiseOrValue<U128> {
+        let mut receipt = receipt;
+        receipt.amount_out = promise_result_as_success()
+            .and_then(|value| serde_json::from_slice::<U128>(&value).ok())
+            .unwrap_or(U128(0));
+        if receipt.amount_out.0 == 0 {
+            self.store_swap_receipt(receipt_id, receipt);
+            return PromiseOrValue::Value(U128(0));
+        }
+
+        ext_dex::ext(receipt.dex_account.clone())
+            .with_static_gas(utils::tera_gas(20))
+            .with_attached_deposit(utils::NO_DEPOSIT)
+            .withdraw(receipt.token_out.clone(), receipt.amount_out)
+            .then(
+                ext_self::ext(current_account_id())
+                    .with_static_gas(utils::tera_gas(70))
+                    .with_attached_deposit(utils::NO_DEPOSIT)
+                    .swap_withdraw_callback(receipt_id, receipt, swap_message),
+            )
+            .into()
+    }
+
+    /// Credits the withdrawn output of the swap to the balance of the sender and initializes the transfer
+    /// of the `swap_message` from it. The output stays on the balance of the sender if it's less than
+    /// the `min_amount_out` or the transfer can't be initialized. The receipt is stored in this contract
+    /// to be withdrawn with `withdraw_swap_receipt()` if the withdrawal fails.
+    #[private]
+    pub fn swap_withdraw_callback(
+        &mut self,
+        #[serializer(borsh)] receipt_id: U128,
+        #[serializer(borsh)] receipt: SwapReceipt,
+        #[serializer(borsh)] swap_message: Option<SwapAndTransferMessage>,
+    ) -> U128 {
+        let withdrawn_amount = promise_result_as_success()
+            .and_then(|value| serde_json::from_slice::<U128>(&value).ok())
+            .unwrap_or(U128(0));
+        if withdrawn_amount != receipt.amount_out {
+            self.store_swap_receipt(receipt_id, receipt);
+            return U128(0);
+        }
+
+        let sender_id = receipt.sender_id.clone();
+        let amount_out = receipt.amount_out;
+        self.increase_balance(&sender_id, &receipt.token_out, &amount_out.0);
+
+        FastBridgeEvent::FastBridgeSwapEvent {
+            sender_id: receipt.sender_id,
+            token_in: receipt.token_in,
+            amount_in: receipt.amount_in,
+            token_out: receipt.token_out,
+            amount_out,
+        }
+        .emit();
+
+        if let Some(swap_message) = swap_message {
+            if amount_out >= swap_message.min_amount_out {
+                // The transfer isn't a part of the result, so its failure doesn't refund the swapped input
+                self.init_transfer_internal(
+                    swap_message.transfer_message,
+                    swap_message.transfer_options,
+                    sender_id,
+                    None,
+                );
+            }
+        }
+
+        U128(0)
+    }
+
+    /// Retries the withdrawal of the swap output that failed to be withdrawn from the DEX. The output
+    /// is credited to the balance of the sender of the swap, who is the only one allowed to call this.
+    pub fn withdraw_swap_receipt(&mut self, receipt_id: U128) -> Promise {
+        let receipt = self
+            .swap_receipts
+            .remove(&receipt_id.0.to_string())
+            .unwrap_or_else(|| env::panic_str("The swap receipt isn't found"));
+        require!(
+            receipt.sender_id == env::predecessor_account_id(),
+            "Only the sender of the swap can withdraw its output"
+        );
+
+        self.read_swap_receipt(receipt_id, receipt, None)
+    }
+
+    /// Gets the receipt of the swap which output failed to be withdrawn from the DEX.
+    pub fn get_swap_receipt(&self, receipt_id: U128) -> Option<SwapReceipt> {
+        self.swap_receipts.get(&receipt_id.0.to_string())
+    }
+
+    /// Sets the DEX that swaps the tokens for the swap-and-transfer messages, `None` disables swaps.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn set_dex_account(&mut self, dex_account: Option<AccountId>) {
+        let previous_dex_account = std::mem::replace(&mut self.dex_account, dex_account.clone());
+
+        FastBridgeEvent::FastBridgeSetDexAccountEvent {
+            previous_dex_account,
+            dex_account,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn get_dex_account(&self) -> Option<AccountId> {
+        self.dex_account.clone()
+    }
+}
+
+impl FastBridge {
+    /// Swaps the `amount_in` of the `token_in` received from the `sender_id` on the DEX to the token
+    /// of the transfer and initializes the transfer with the output. The `msg` is the Base64-encoded
+    /// `SwapAndTransferMessage`, the `min_amount_out` of it should cover the transfer amount and the fee
+    /// in the same token. The input is returned to the `sender_id` if the swap fails.
+    pub(crate) fn swap_and_init_transfer(
+        &mut self,
+        token_in: AccountId,
+        sender_id: AccountId,
+        amount_in: U128,
+        msg: &str,
+    ) -> Promise {
+        let dex_account = self
+            .dex_account
+            .clone()
+            .unwrap_or_else(|| env::panic_str("Swaps are disabled"));
+        let decoded_base64 =
+            base64::decode(msg).unwrap_or_else(|_| env::panic_str("Invalid base64 message"));
+        let swap_message =
+            SwapAndTransferMessage::try_from_slice(&decoded_base64).unwrap_or_else(|_| {
+                env::panic_str("Invalid borsh format of the `SwapAndTransferMessage`")
+            });
+
+        let transfer_message = &swap_message.transfer_message;
+        let token_out = transfer_message.transfer.token_near.clone();
+        self.check_whitelist_token_and_account(&token_out, &sender_id);
+        let required_amount = if transfer_message.fee.token == token_out {
+            transfer_message.transfer.amount.0 + transfer_message.fee.amount.0
+        } else {
+            transfer_message.transfer.amount.0
+        };
+        require!(
+            swap_message.min_amount_out.0 >= required_amount,
+            format!(
+                "The minimal swap output should cover the transfer amount {}",
+                required_amount
+            )
+        );
+
+        self.swap_nonce += 1;
+        let receipt_id = U128(self.swap_nonce);
+        let swap_request = SwapRequest {
+            token_out,
+            min_amount_out: swap_message.min_amount_out,
+            receipt_id,
+        };
+        ext_token::ext(token_in.clone())
+            .with_static_gas(utils::tera_gas(35))
+            .with_attached_deposit(1)
+            .ft_transfer_call(
+                dex_account.clone(),
+                amount_in,
+                None,
+                serde_json::to_string(&swap_request).unwrap(),
+            )
+            .then(
+                ext_self::ext(current_account_id())
+                    .with_static_gas(utils::tera_gas(120))
+                    .with_attached_deposit(utils::NO_DEPOSIT)
+                    .swap_callback(
+                        sender_id,
+                        token_in,
+                        amount_in,
+                        dex_account,
+                        receipt_id,
+                        swap_message,
+                    ),
+            )
+    }
+
+    fn read_swap_receipt(
+        &self,
+        receipt_id: U128,
+        receipt: SwapReceipt,
+        swap_message: Option<SwapAndTransferMessage>,
+    ) -> Promise {
+        ext_dex::ext(receipt.dex_account.clone())
+            .with_static_gas(utils::tera_gas(5))
+            .with_attached_deposit(utils::NO_DEPOSIT)
+            .get_swap_receipt(current_account_id(), receipt_id)
+            .then(
+                ext_self::ext(current_account_id())
+                    .with_static_gas(utils::tera_gas(100))
+                    .with_attached_deposit(utils::NO_DEPOSIT)
+                    .swap_receipt_callback(receipt_id, receipt, swap_message),
+            )
+    }
+
+    fn store_swap_receipt(&mut self, receipt_id: U128, receipt: SwapReceipt) {
+        self.swap_receipts
+            .insert(&receipt_id.0.to_string(), &receipt);
+
+        FastBridgeEvent::FastBridgeSwapWithdrawFailedEvent {
+            receipt_id,
+            receipt,
+        }
+        .emit();
+    }
+}
@@ -11,7 +11,8 @@ mod integration_tests {
     use workspaces::operations::Function;
     use workspaces::{Account, AccountId, Contract};
 
-    use crate::UnlockProof;
+    use crate::swap::{SwapAndTransferMessage, SWAP_MESSAGE_PREFIX};
+    use crate::{TransferOptions, UnlockProof};
 
     const ETH_BRIDGE_ADDRESS: &str = "6b175474e89094c44da98b954eedeac495271d0f";
     const BRIDGE_WASM_FILEPATH: &str = "../target/wasm32-unknown-unknown/release/fastbridge.wasm";
@@ -24,6 +25,7 @@ mod integration_tests {
 
     const MOCK_FT_RECEIVER_WASM_FILEPATH: &str =
         "../target/wasm32-unknown-unknown/release/mock_ft_receiver.wasm";
+    const MOCK_DEX_WASM_FILEPATH: &str = "../target/wasm32-unknown-unknown/release/mock_dex.wasm";
 
     #[derive(serde::Serialize)]
     struct InitArgs {
@@ -426,6 +428,18 @@ mod integration_tests {
         Ok(())
     }
 
+    async fn deposit_transfer_storage(bridge: &Contract, account: &Account) -> anyhow::Result<()> {
+        let result = account
+            .call(bridge.id(), "transfer_storage_deposit")
+            .args_json(json!({}))
+            .max_gas()
+            .deposit(ONE_NEAR)
+            .transact()
+            .await?;
+        assert!(result.is_success(), "{:?}", result);
+        Ok(())
+    }
+
     async fn transfer_tokens(
         bridge: &Account,
         account: &Account,
@@ -506,7 +520,8 @@ mod integration_tests {
             0
         );
 
-        // Init token transfer twice
+        // Init token transfer twice, the storage of both transfers is covered by one deposit
+        deposit_transfer_storage(&test_data.bridge, alice).await?;
         let total_transfer_amount: u128 = 10;
         let total_fee_amount: u128 = 10;
         let duration_secs = 5;
@@ -670,6 +685,7 @@ mod integration_tests {
         );
 
         // Init token transfer
+        deposit_transfer_storage(&test_data.bridge, alice).await?;
         let total_transfer_amount: u128 = 10;
         let total_fee_amount: u128 = 10;
         let duration_secs = 5;
@@ -759,4 +775,272 @@ mod integration_tests {
 
         Ok(())
     }
+
+    /// Deploys the input token of the swaps owned by alice and the DEX that swaps it to the transfer token
+    /// at the `rate_numerator / rate_denominator` rate. Returns the input token and the DEX.
+    async fn deploy_dex(
+        test_data: &TestData,
+        rate_numerator: u128,
+        rate_denominator: u128,
+    ) -> anyhow::Result<(Contract, Contract)> {
+        let alice = &test_data.accounts[0];
+        let token_in = alice
+            .create_subaccount("token_in")
+            .initial_balance(10 * ONE_NEAR)
+            .transact()
+            .await?
+            .into_result()?
+            .deploy(&std::fs::read(MOCK_TOKEN_WASM_FILEPATH)?)
+            .await?
+            .into_result()?;
+        let dex = alice
+            .create_subaccount("dex")
+            .initial_balance(10 * ONE_NEAR)
+            .transact()
+            .await?
+            .into_result()?
+            .deploy(&std::fs::read(MOCK_DEX_WASM_FILEPATH)?)
+            .await?
+            .into_result()?;
+
+        let result = token_in
+            .call("new")
+            .args_json(json!({
+                "owner_id": alice.id(),
+                "total_supply": "1000",
+                "metadata": {
+                    "spec": "ft-1.0.0",
+                    "name": "Input Token",
+                    "symbol": "IN",
+                    "decimals": 24
+                },
+            }))
+            .max_gas()
+            .transact()
+            .await?;
+        assert!(result.is_success(), "{:?}", result);
+
+        for account_id in [test_data.bridge.id(), dex.id()] {
+            let result = token_in
+                .call("storage_deposit")
+                .args_json(json!({
+                    "account_id": account_id,
+                }))
+                .max_gas()
+                .deposit(ONE_NEAR)
+                .transact()
+                .await?;
+            assert!(result.is_success(), "{:?}", result);
+        }
+
+        let result = dex
+            .call("new")
+            .args_json(json!({
+                "rate_numerator": rate_numerator.to_string(),
+                "rate_denominator": rate_denominator.to_string(),
+            }))
+            .max_gas()
+            .transact()
+            .await?;
+        assert!(result.is_success(), "{:?}", result);
+
+        // The DEX pays the swap outputs from its own balance of the transfer token
+        let result = test_data
+            .token
+            .call("mint")
+            .args_json(json!({
+                "account_id": dex.id(),
+                "amount": "1000"
+            }))
+            .max_gas()
+            .transact()
+            .await?;
+        assert!(result.is_success(), "{:?}", result);
+
+        let result = test_data
+            .bridge
+            .call("acl_grant_role")
+            .args_json(json!({
+                "role": "ConfigManager",
+                "account_id": test_data.bridge.id(),
+            }))
+            .max_gas()
+            .transact()
+            .await?;
+        assert!(result.is_success(), "{:?}", result);
+
+        let result = test_data
+            .bridge
+            .call("set_dex_account")
+            .args_json(json!({
+                "dex_account": dex.id(),
+            }))
+            .max_gas()
+            .transact()
+            .await?;
+        assert!(result.is_success(), "{:?}", result);
+
+        Ok((token_in, dex))
+    }
+
+    async fn swap_and_transfer_tokens(
+        test_data: &TestData,
+        token_in: &Contract,
+        amount_in: u128,
+        min_amount_out: u128,
+        transfer_amount: u128,
+        fee_amount: u128,
+    ) -> Result<workspaces::result::ExecutionFinalResult, workspaces::error::Error> {
+        let valid_till = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .add(std::time::Duration::from_secs(60))
+            .as_nanos()
+            .try_into()
+            .expect("Can't convert Duration to u64");
+        let swap_message = SwapAndTransferMessage {
+            min_amount_out: min_amount_out.into(),
+            transfer_message: TransferMessage {
+                valid_till,
+                transfer: TransferDataEthereum {
+                    token_near: test_data.token.id().to_string().parse().unwrap(),
+                    token_eth: fast_bridge_common::EthAddress([0; 20]),
+                    amount: transfer_amount.into(),
+                },
+                fee: TransferDataNear {
+                    token: test_data.token.id().to_string().parse().unwrap(),
+                    amount: fee_amount.into(),
+                },
+                recipient: fast_bridge_common::EthAddress([1; 20]),
+                valid_till_block_height: None,
+                aurora_sender: None,
+            },
+            transfer_options: TransferOptions::default(),
+        };
+        let msg = format!(
+            "{}{}",
+            SWAP_MESSAGE_PREFIX,
+            near_sdk::base64::encode(swap_message.try_to_vec().unwrap())
+        );
+
+        test_data.accounts[0]
+            .call(token_in.id(), "ft_transfer_call")
+            .args_json(json!({
+                "receiver_id": test_data.bridge.id(),
+                "amount": amount_in.to_string(),
+                "msg": msg,
+            }))
+            .max_gas()
+            .deposit(ONE_YOCTO)
+            .transact()
+            .await
+    }
+
+    async fn get_pending_transfer(
+        bridge: &Contract,
+        nonce: u128,
+    ) -> anyhow::Result<near_sdk::serde_json::Value> {
+        Ok(bridge
+            .view("get_pending_transfer")
+            .args_json(json!({
+                "id": nonce.to_string(),
+            }))
+            .await?
+            .json()?)
+    }
+
+    #[tokio::test]
+    async fn test_swap_and_transfer() -> anyhow::Result<()> {
+        let test_data = common_test_unlock_and_withdraw_data().await?;
+        let (token_in, dex) = deploy_dex(&test_data, 1, 1).await?;
+        let alice = &test_data.accounts[0];
+        deposit_transfer_storage(&test_data.bridge, alice).await?;
+
+        let amount_in: u128 = 30;
+        let transfer_amount: u128 = 10;
+        let fee_amount: u128 = 10;
+        let result = swap_and_transfer_tokens(
+            &test_data,
+            &token_in,
+            amount_in,
+            transfer_amount + fee_amount,
+            transfer_amount,
+            fee_amount,
+        )
+        .await?;
+        assert!(result.is_success(), "{:?}", result);
+        let used_amount: U128 = result.json()?;
+        assert_eq!(used_amount.0, amount_in);
+
+        // The input is swapped and the exact output is withdrawn from the DEX to the bridge
+        assert_eq!(
+            get_token_balance(&token_in, alice.id()).await?.0,
+            1000 - amount_in
+        );
+        assert_eq!(get_token_balance(&token_in, dex.id()).await?.0, amount_in);
+        assert_eq!(
+            get_token_balance(&test_data.token, test_data.bridge.id())
+                .await?
+                .0,
+            1000 + amount_in
+        );
+        let dex_deposit: U128 = dex
+            .view("get_deposit")
+            .args_json(json!({
+                "account_id": test_data.bridge.id(),
+                "token_id": test_data.token.id(),
+            }))
+            .await?
+            .json()?;
+        assert_eq!(dex_deposit.0, 0);
+
+        // The transfer is initialized from the output and the rest of it stays on the balance of alice
+        assert!(!get_pending_transfer(&test_data.bridge, 1).await?.is_null());
+        assert_eq!(
+            get_bridge_balance(&test_data.bridge, alice.id(), test_data.token.id())
+                .await?
+                .0,
+            amount_in - transfer_amount - fee_amount
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_swap_and_transfer_with_failed_swap() -> anyhow::Result<()> {
+        let test_data = common_test_unlock_and_withdraw_data().await?;
+        // The output of the swap is a half of the input, which is less than the minimal output
+        let (token_in, dex) = deploy_dex(&test_data, 1, 2).await?;
+        let alice = &test_data.accounts[0];
+        deposit_transfer_storage(&test_data.bridge, alice).await?;
+
+        let amount_in: u128 = 30;
+        let transfer_amount: u128 = 10;
+        let fee_amount: u128 = 10;
+        let result = swap_and_transfer_tokens(
+            &test_data,
+            &token_in,
+            amount_in,
+            transfer_amount + fee_amount,
+            transfer_amount,
+            fee_amount,
+        )
+        .await?;
+        assert!(result.is_success(), "{:?}", result);
+        let used_amount: U128 = result.json()?;
+        assert_eq!(used_amount.0, 0);
+
+        // The input is refunded to alice and no transfer is initialized
+        assert_eq!(get_token_balance(&token_in, alice.id()).await?.0, 1000);
+        assert_eq!(get_token_balance(&token_in, dex.id()).await?.0, 0);
+        assert_eq!(
+            get_token_balance(&test_data.token, test_data.bridge.id())
+                .await?
+                .0,
+            1000
+        );
+        assert!(get_pending_transfer(&test_data.bridge, 1).await?.is_null());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,164 @@
+use near_plugins::access_control_any;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, AccountId};
+
+use crate::*;
+
+/// The Ethereum counterpart of the NEAR token.
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenPair {
+    pub token_eth: EthAddress,
+    pub near_decimals: u8,
+    pub eth_decimals: u8,
+}
+
+/// The maximum difference of the token decimals that can be converted without overflowing `u128`.
+const MAX_DECIMALS_DIFFERENCE: u8 = 38;
+
+#[near_bindgen]
+impl FastBridge {
+    /// Registers the Ethereum token that corresponds to the `token_near`. Transfers of the `token_near`
+    /// are allowed only to the registered Ethereum token.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_near` - The NEP-141 token account.
+    /// * `token_eth` - The hex-encoded address of the ERC-20 token on Ethereum.
+    /// * `near_decimals` - The decimals of the NEP-141 token.
+    /// * `eth_decimals` - The decimals of the ERC-20 token, the transferred amount is converted to them on initialization.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn set_token_pair(
+        &mut self,
+        token_near: AccountId,
+        token_eth: String,
+        near_decimals: u8,
+        eth_decimals: u8,
+    ) {
+        require!(
+            near_decimals.abs_diff(eth_decimals) <= MAX_DECIMALS_DIFFERENCE,
+            "The difference of the token decimals is too big"
+        );
+
+        let token_pair = TokenPair {
+            token_eth: get_eth_address(token_eth),
+            near_decimals,
+            eth_decimals,
+        };
+        let previous_token_pair = self.token_pairs.insert(&token_near, &token_pair);
+
+        FastBridgeEvent::FastBridgeSetTokenPairEvent {
+            token_near,
+            previous_token_pair,
+            token_pair,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    /// Removes the registered Ethereum counterpart of the `token_near`.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn remove_token_pair(&mut self, token_near: AccountId) -> Option<TokenPair> {
+        let previous_token_pair = self.token_pairs.remove(&token_near);
+
+        if previous_token_pair.is_some() {
+            FastBridgeEvent::FastBridgeRemoveTokenPairEvent {
+                token_near,
+                previous_token_pair: previous_token_pair.clone(),
+                changed_by: env::predecessor_account_id(),
+            }
+            .emit();
+        }
+
+        previous_token_pair
+    }
+
+    /// Enables or disables the requirement for every transferred token to have a registered pair.
+    /// Registered pairs are enforced regardless of this setting.
+    ///
+    /// The function is allowed to be called only by accounts that have `ConfigManager` or `DAO` roles.
+    #[access_control_any(roles(Role::ConfigManager, Role::DAO))]
+    pub fn set_token_pair_check_enabled(&mut self, enabled: bool) {
+        let previous_enabled = self.is_token_pair_check_enabled;
+        self.is_token_pair_check_enabled = enabled;
+
+        FastBridgeEvent::FastBridgeSetTokenPairCheckEnabledEvent {
+            previous_enabled,
+            enabled,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn is_token_pair_check_enabled(&self) -> bool {
+        self.is_token_pair_check_enabled
+    }
+
+    pub fn get_token_pair(&self, token_near: AccountId) -> Option<TokenPair> {
+        self.token_pairs.get(&token_near)
+    }
+
+    /// Returns a page of the registered token pairs, starting at the specified `from_index`
+    /// and containing a maximum of `limit` pairs.
+    pub fn get_token_pairs(&self, from_index: usize, limit: usize) -> Vec<(AccountId, TokenPair)> {
+        self.token_pairs
+            .iter()
+            .skip(from_index)
+            .take(limit)
+            .collect::<Vec<_>>()
+    }
+}
+
+impl FastBridge {
+    /// Converts the transferred amount to the decimals of the Ethereum token. If the token pair
+    /// isn't registered, the amount is the same on both sides.
+    pub(crate) fn get_transfer_amount_eth(&self, transfer: &TransferDataEthereum) -> u128 {
+        let Some(token_pair) = self.token_pairs.get(&transfer.token_near) else {
+            return transfer.amount.0;
+        };
+
+        if token_pair.near_decimals >= token_pair.eth_decimals {
+            let factor = 10u128.pow((token_pair.near_decimals - token_pair.eth_decimals).into());
+            require!(
+                transfer.amount.0 % factor == 0,
+                format!(
+                    "The amount {} can't be converted to {} decimals of the Ethereum token without a remainder",
+                    transfer.amount.0, token_pair.eth_decimals
+                )
+            );
+            transfer.amount.0 / factor
+        } else {
+            let factor = 10u128.pow((token_pair.eth_decimals - token_pair.near_decimals).into());
+            transfer
+                .amount
+                .0
+                .checked_mul(factor)
+                .unwrap_or_else(|| env::panic_str("The Ethereum amount overflows"))
+        }
+    }
+
+    pub(crate) fn check_token_pair(&self, transfer: &TransferDataEthereum) {
+        match self.token_pairs.get(&transfer.token_near) {
+            Some(token_pair) => require!(
+                token_pair.token_eth == transfer.token_eth,
+                format!(
+                    "The token `{}` can't be transferred to the Ethereum token `{}`, expected `{}`",
+                    transfer.token_near,
+                    hex::encode(transfer.token_eth.0),
+                    hex::encode(token_pair.token_eth.0)
+                )
+            ),
+            None => require!(
+                !self.is_token_pair_check_enabled,
+                format!(
+                    "The token pair for `{}` is not registered",
+                    transfer.token_near
+                )
+            ),
+        }
+    }
+}
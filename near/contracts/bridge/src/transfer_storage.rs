@@ -0,0 +1,136 @@
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, AccountId, Promise};
+
+use crate::*;
+
+#[near_bindgen]
+impl FastBridge {
+    /// Deposits the attached NEAR to cover the storage occupied by pending transfers of the `account_id`.
+    ///
+    /// Every pending transfer locks a storage bond from this balance in `store_transfers()`,
+    /// the bond is returned to the balance of the originator once the transfer is removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_id` - The account for which the deposit is made. If `None` is provided, the caller account is used.
+    ///
+    /// # Returns
+    ///
+    /// The storage balance of the account after the deposit.
+    #[payable]
+    #[pause]
+    pub fn transfer_storage_deposit(&mut self, account_id: Option<AccountId>) -> U128 {
+        let amount = env::attached_deposit();
+        require!(
+            amount > 0,
+            "The attached deposit should be a positive number"
+        );
+
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        self.increase_transfer_storage_balance(&account_id, amount);
+
+        FastBridgeEvent::FastBridgeTransferStorageDepositEvent {
+            account_id: account_id.clone(),
+            amount: U128(amount),
+        }
+        .emit();
+
+        self.get_transfer_storage_balance(&account_id)
+    }
+
+    /// Withdraws the specified `amount` of NEAR from the storage balance of the caller.
+    /// Only the part of the balance that isn't locked by pending transfers can be withdrawn.
+    ///
+    /// Requires an attached deposit of exactly 1 yoctoNEAR.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount to withdraw. If `None` is provided, the entire balance of the caller will be withdrawn.
+    #[payable]
+    pub fn transfer_storage_withdraw(&mut self, amount: Option<U128>) -> Promise {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let balance = self.get_transfer_storage_balance(&account_id);
+        let amount = amount.unwrap_or(balance);
+
+        require!(amount.0 > 0, "The amount should be a positive number");
+        require!(amount <= balance, "Insufficient storage balance");
+
+        self.transfer_storage_balances
+            .insert(&account_id, &(balance.0 - amount.0));
+
+        FastBridgeEvent::FastBridgeTransferStorageWithdrawEvent {
+            account_id: account_id.clone(),
+            amount,
+        }
+        .emit();
+
+        Promise::new(account_id).transfer(amount.0)
+    }
+
+    /// Gets the storage balance of the account that is available to cover new pending transfers.
+    pub fn get_transfer_storage_balance(&self, account_id: &AccountId) -> U128 {
+        self.transfer_storage_balances
+            .get(account_id)
+            .unwrap_or(0)
+            .into()
+    }
+
+    /// Gets the storage bond locked by the pending transfer with the given `nonce`.
+    pub fn get_transfer_storage_bond(&self, nonce: U128) -> U128 {
+        self.transfer_storage_bonds
+            .get(&nonce.0.to_string())
+            .unwrap_or(0)
+            .into()
+    }
+}
+
+impl FastBridge {
+    pub(crate) fn increase_transfer_storage_balance(
+        &mut self,
+        account_id: &AccountId,
+        amount: u128,
+    ) {
+        let balance = self.get_transfer_storage_balance(account_id).0 + amount;
+        self.transfer_storage_balances.insert(account_id, &balance);
+    }
+
+    /// Charges the `account_id` for the storage used since `initial_storage_usage` and locks it as
+    /// the bond of the transfer with the given `transfer_id`.
+    pub(crate) fn lock_transfer_storage_bond(
+        &mut self,
+        account_id: &AccountId,
+        transfer_id: &String,
+        initial_storage_usage: u64,
+    ) {
+        // The bond entry is a part of the storage occupied by the transfer, so it's created before the measurement
+        self.transfer_storage_bonds.insert(transfer_id, &0);
+
+        let used_bytes = env::storage_usage().saturating_sub(initial_storage_usage);
+        let bond = env::storage_byte_cost() * u128::from(used_bytes);
+        let balance = self.get_transfer_storage_balance(account_id).0;
+
+        require!(
+            balance >= bond,
+            format!(
+                "Not enough storage balance to store the transfer, required: {}, available: {}",
+                bond, balance
+            )
+        );
+
+        self.transfer_storage_balances
+            .insert(account_id, &(balance - bond));
+        self.transfer_storage_bonds.insert(transfer_id, &bond);
+    }
+
+    /// Returns the storage bond of the transfer with the given `transfer_id` to the balance of the `account_id`.
+    pub(crate) fn refund_transfer_storage_bond(
+        &mut self,
+        account_id: &AccountId,
+        transfer_id: &String,
+    ) {
+        if let Some(bond) = self.transfer_storage_bonds.remove(transfer_id) {
+            self.increase_transfer_storage_balance(account_id, bond);
+        }
+    }
+}
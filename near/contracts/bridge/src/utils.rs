@@ -1,8 +1,12 @@
 pub const TGAS: near_sdk::Gas = near_sdk::Gas::ONE_TERA;
 pub const NO_DEPOSIT: u128 = 0;
+/// The basis points of the whole amount
+pub const MAX_FEE_BPS: u32 = 10_000;
 // The slot number of the storage `mapping(bytes32 => bool) public processedHashes;` in the contract `eth/contracts/EthErc20FastBridge.sol`.
 // This number can be found in the generated storage layout `eth/contracts/res/storage_layout.txt
 const STORAGE_KEY_SLOT: u32 = 302;
+// The slot number of the storage `mapping(bytes32 => uint256) public filledAmounts;` in the same contract.
+const FILLED_AMOUNTS_STORAGE_KEY_SLOT: u32 = 303;
 
 pub fn tera_gas(gas: u64) -> near_sdk::Gas {
     TGAS * gas
@@ -13,25 +17,64 @@ pub fn get_eth_storage_key_hash(
     recipient: fast_bridge_common::EthAddress,
     nonce: eth_types::U256,
     amount: eth_types::U256,
+    payload_hash: Option<[u8; 32]>,
 ) -> Vec<u8> {
-    let slot = eth_types::U256(STORAGE_KEY_SLOT.into());
+    get_mapping_storage_key_hash(
+        STORAGE_KEY_SLOT,
+        get_transfer_id(token, recipient, nonce, amount, payload_hash),
+    )
+}
+
+/// Gets the storage key hash of the filled amount of the transfer that allows partial fills.
+pub fn get_eth_filled_amount_storage_key_hash(
+    token: fast_bridge_common::EthAddress,
+    recipient: fast_bridge_common::EthAddress,
+    nonce: eth_types::U256,
+    amount: eth_types::U256,
+) -> Vec<u8> {
+    get_mapping_storage_key_hash(
+        FILLED_AMOUNTS_STORAGE_KEY_SLOT,
+        get_transfer_id(token, recipient, nonce, amount, None),
+    )
+}
+
+/// Encodes the `uint256` storage value the same way as it's stored in the storage trie,
+/// the zero value is absent in the trie.
+pub fn get_eth_storage_value(value: u128) -> Vec<u8> {
+    if value == 0 {
+        return vec![];
+    }
+
+    let be_value = value.to_be_bytes();
+    let first_non_zero = be_value.iter().position(|byte| *byte != 0).unwrap_or(0);
+    rlp::encode(&be_value[first_non_zero..].to_vec()).to_vec()
+}
+
+fn get_mapping_storage_key_hash(slot: u32, key: Vec<u8>) -> Vec<u8> {
+    let slot = eth_types::U256(slot.into());
     let mut be_slot = [0u8; 32];
     slot.0.to_big_endian(&mut be_slot);
 
-    let encoded_slot_key = [
-        get_transfer_id(token, recipient, nonce, amount).as_slice(),
-        be_slot.as_slice(),
-    ]
-    .concat();
+    let encoded_slot_key = [key.as_slice(), be_slot.as_slice()].concat();
 
     near_sdk::env::keccak256(&near_sdk::env::keccak256(&encoded_slot_key))
 }
 
+/// Calculates `value * numerator / denominator` without the intermediate overflow.
+pub fn mul_div(value: u128, numerator: u128, denominator: u128) -> u128 {
+    let result = eth_types::U256(value.into()).0 * eth_types::U256(numerator.into()).0
+        / eth_types::U256(denominator.into()).0;
+    result.as_u128()
+}
+
+/// Calculates the `processedHash` of the transfer in `eth/contracts/EthErc20FastBridge.sol`,
+/// the `payload_hash` is appended only for the transfers filled with `transferTokensWithPayload`.
 pub fn get_transfer_id(
     token: fast_bridge_common::EthAddress,
     recipient: fast_bridge_common::EthAddress,
     nonce: eth_types::U256,
     amount: eth_types::U256,
+    payload_hash: Option<[u8; 32]>,
 ) -> Vec<u8> {
     let mut be_nonce = [0u8; 32];
     nonce.0.to_big_endian(&mut be_nonce);
@@ -43,6 +86,9 @@ pub fn get_transfer_id(
         recipient.0.as_slice(),
         be_nonce.as_slice(),
         be_amount.as_slice(),
+        payload_hash
+            .as_ref()
+            .map_or(&[][..], |payload_hash| payload_hash.as_slice()),
     ]
     .concat();
 
@@ -61,6 +107,7 @@ mod tests {
             get_eth_address("e6220257D157Ec7b481290fD10d2037Cf0E83Ea5".to_owned()),
             eth_types::U256(360u128.into()),
             eth_types::U256(9998u128.into()),
+            None,
         );
         assert_eq!(
             hex::encode(key),
@@ -72,6 +119,7 @@ mod tests {
             get_eth_address("b003DB6E49C55c2fD4Bca506ddDB408039D190c8".to_owned()),
             eth_types::U256(280u128.into()),
             eth_types::U256(724086u128.into()),
+            None,
         );
         assert_eq!(
             hex::encode(key),
@@ -86,6 +134,7 @@ mod tests {
             get_eth_address("3Cb1d11dAE619d489C12bD30e229Ae13bb707409".to_owned()),
             eth_types::U256(18u128.into()),
             eth_types::U256(99970000000u128.into()),
+            None,
         );
 
         assert_eq!(
@@ -98,6 +147,7 @@ mod tests {
             get_eth_address("b003DB6E49C55c2fD4Bca506ddDB408039D190c8".to_owned()),
             eth_types::U256(7.into()),
             eth_types::U256(987400000000000000u128.into()),
+            None,
         );
 
         assert_eq!(
@@ -105,4 +155,48 @@ mod tests {
             "8a0b8e93348a672f7eb47b661e2c8ff199344117ab6ea4183c2af5af753a651b"
         );
     }
+
+    #[test]
+    fn test_get_transfer_id_with_payload() {
+        let payload_hash = near_sdk::env::keccak256(&hex::decode("12345678").unwrap());
+        let payload_hash: Option<[u8; 32]> = Some(payload_hash.try_into().unwrap());
+
+        let transfer_id = get_transfer_id(
+            get_eth_address("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_owned()),
+            get_eth_address("3Cb1d11dAE619d489C12bD30e229Ae13bb707409".to_owned()),
+            eth_types::U256(18u128.into()),
+            eth_types::U256(99970000000u128.into()),
+            payload_hash,
+        );
+        assert_eq!(
+            hex::encode(transfer_id),
+            "e0ba4ec9fc8e16e21c023d2a3dffab7056457846a172c144277907f4c93c14a5"
+        );
+
+        let key = get_eth_storage_key_hash(
+            get_eth_address("07865c6E87B9F70255377e024ace6630C1Eaa37F".to_owned()),
+            get_eth_address("e6220257D157Ec7b481290fD10d2037Cf0E83Ea5".to_owned()),
+            eth_types::U256(360u128.into()),
+            eth_types::U256(9998u128.into()),
+            payload_hash,
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f6486555c8f5db72c38c1991cdca6239899a06e1b70518d32eb6e60e47e37891"
+        );
+    }
+
+    #[test]
+    fn test_get_eth_storage_value() {
+        assert_eq!(get_eth_storage_value(0), Vec::<u8>::new());
+        assert_eq!(get_eth_storage_value(0x7f), vec![0x7f]);
+        assert_eq!(get_eth_storage_value(0x80), vec![0x81, 0x80]);
+        assert_eq!(get_eth_storage_value(256), vec![0x82, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn test_mul_div() {
+        assert_eq!(mul_div(u128::MAX, 3, 4), u128::MAX / 4 * 3 + 2);
+        assert_eq!(mul_div(100, 1, 3), 33);
+    }
 }
@@ -1,4 +1,5 @@
 use near_plugins::access_control_any;
+use near_sdk::collections::UnorderedSet;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{env, AccountId};
 
@@ -12,6 +13,35 @@ pub enum WhitelistMode {
     CheckAccountAndToken,
 }
 
+/// A whitelist entry of the account, which is scoped to the `token` or applies to all tokens if the `token` is `None`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountWhitelistEntry {
+    pub token: Option<AccountId>,
+    pub account: AccountId,
+}
+
+/// The result of processing a single entry by the batch whitelist methods.
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum WhitelistEntryStatus {
+    Added,
+    AlreadyWhitelisted,
+    Removed,
+    NotWhitelisted,
+    TokenModeNotSet,
+}
+
+/// The whitelist rule that allows the account to use the token.
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AccountWhitelistRule {
+    /// The account is whitelisted for the token with the combined key {token}:{account}
+    TokenAccount,
+    /// The account is whitelisted for all tokens with the account key
+    Account,
+}
+
 fn get_token_account_key(token: Option<&AccountId>, account: &AccountId) -> String {
     if let Some(token) = token {
         format!("{}:{}", token, account)
@@ -20,24 +50,57 @@ fn get_token_account_key(token: Option<&AccountId>, account: &AccountId) -> Stri
     }
 }
 
+pub(crate) fn new_account_whitelist_tokens(account: &AccountId) -> UnorderedSet<AccountId> {
+    UnorderedSet::new(
+        [
+            StorageKey::AccountWhitelistTokensPrefix
+                .try_to_vec()
+                .unwrap()
+                .as_slice(),
+            account.try_to_vec().unwrap().as_slice(),
+        ]
+        .concat(),
+    )
+}
+
 #[near_bindgen]
 impl FastBridge {
     #[access_control_any(roles(Role::WhitelistManager, Role::DAO))]
     pub fn set_token_whitelist_mode(&mut self, token: AccountId, mode: WhitelistMode) {
-        self.whitelist_tokens.insert(&token, &mode);
+        self.set_token_whitelist_mode_internal(token, mode);
     }
 
     #[access_control_any(roles(Role::WhitelistManager, Role::DAO))]
-    pub fn add_token_to_account_whitelist(&mut self, token: Option<AccountId>, account: AccountId) {
-        if let Some(token) = &token {
-            assert!(
-                self.whitelist_tokens.get(token).is_some(),
-                "The whitelisted token mode is not set",
-            );
+    pub fn set_token_whitelist_modes(&mut self, modes: Vec<(AccountId, WhitelistMode)>) {
+        for (token, mode) in modes {
+            self.set_token_whitelist_mode_internal(token, mode);
         }
+    }
 
-        self.whitelist_accounts
-            .insert(&get_token_account_key(token.as_ref(), &account));
+    #[access_control_any(roles(Role::WhitelistManager, Role::DAO))]
+    pub fn add_token_to_account_whitelist(&mut self, token: Option<AccountId>, account: AccountId) {
+        let status = self.add_account_whitelist_entry(token, account);
+        assert!(
+            status != WhitelistEntryStatus::TokenModeNotSet,
+            "The whitelisted token mode is not set",
+        );
+    }
+
+    /// Adds the `entries` to the account whitelist. Unlike `add_token_to_account_whitelist()`, the entries
+    /// with the token mode not set are skipped instead of failing the whole batch.
+    ///
+    /// # Returns
+    ///
+    /// The status of each entry in the same order as the `entries`.
+    #[access_control_any(roles(Role::WhitelistManager, Role::DAO))]
+    pub fn add_tokens_to_accounts_whitelist(
+        &mut self,
+        entries: Vec<AccountWhitelistEntry>,
+    ) -> Vec<WhitelistEntryStatus> {
+        entries
+            .into_iter()
+            .map(|entry| self.add_account_whitelist_entry(entry.token, entry.account))
+            .collect()
     }
 
     #[access_control_any(roles(Role::WhitelistManager, Role::DAO))]
@@ -46,11 +109,49 @@ impl FastBridge {
         token: Option<AccountId>,
         account: AccountId,
     ) -> bool {
-        self.whitelist_accounts
-            .remove(&get_token_account_key(token.as_ref(), &account))
+        self.remove_account_whitelist_entry(token, account) == WhitelistEntryStatus::Removed
+    }
+
+    /// Removes the `entries` from the account whitelist.
+    ///
+    /// # Returns
+    ///
+    /// The status of each entry in the same order as the `entries`.
+    #[access_control_any(roles(Role::WhitelistManager, Role::DAO))]
+    pub fn remove_tokens_from_accounts_whitelist(
+        &mut self,
+        entries: Vec<AccountWhitelistEntry>,
+    ) -> Vec<WhitelistEntryStatus> {
+        entries
+            .into_iter()
+            .map(|entry| self.remove_account_whitelist_entry(entry.token, entry.account))
+            .collect()
+    }
+
+    /// Removes all token-scoped whitelist entries of the `account`. The entry that whitelists
+    /// the account for all tokens is kept.
+    ///
+    /// # Returns
+    ///
+    /// The tokens for which the account was removed from the whitelist.
+    #[access_control_any(roles(Role::WhitelistManager, Role::DAO))]
+    pub fn clear_account_whitelist(&mut self, account: AccountId) -> Vec<AccountId> {
+        let whitelisted_tokens = self
+            .account_whitelist_tokens
+            .get(&account)
+            .map(|tokens| tokens.to_vec())
+            .unwrap_or_default();
+
+        for token in whitelisted_tokens.iter() {
+            self.remove_account_whitelist_entry(Some(token.clone()), account.clone());
+        }
+
+        whitelisted_tokens
     }
 
     pub fn check_whitelist_token_and_account(&self, token: &AccountId, account: &AccountId) {
+        self.check_account_not_blocked(account);
+
         if !self.is_whitelist_mode_enabled {
             return;
         }
@@ -61,10 +162,8 @@ impl FastBridge {
 
         match token_whitelist_mode {
             WhitelistMode::CheckAccountAndToken => {
-                let token_account_key = get_token_account_key(Some(token), account);
                 require!(
-                    self.whitelist_accounts.contains(&token_account_key)
-                        || self.whitelist_accounts.contains(&account.to_string()),
+                    self.get_account_whitelist_rule(token, account).is_some(),
                     format!(
                         "The token `{}` isn't whitelisted for the account `{}`",
                         token, account
@@ -81,7 +180,15 @@ impl FastBridge {
 
     #[access_control_any(roles(Role::WhitelistManager, Role::DAO))]
     pub fn set_whitelist_mode_enabled(&mut self, enabled: bool) {
+        let previous_enabled = self.is_whitelist_mode_enabled;
         self.is_whitelist_mode_enabled = enabled;
+
+        FastBridgeEvent::FastBridgeSetWhitelistModeEnabledEvent {
+            previous_enabled,
+            enabled,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
     }
 
     pub fn get_whitelist_tokens(&self) -> Vec<(AccountId, WhitelistMode)> {
@@ -91,4 +198,171 @@ impl FastBridge {
     pub fn get_whitelist_accounts(&self) -> Vec<String> {
         self.whitelist_accounts.iter().collect::<Vec<_>>()
     }
+
+    /// Returns a page of the whitelisted tokens with their modes, starting at the specified `from_index`
+    /// and containing a maximum of `limit` tokens.
+    pub fn get_whitelist_tokens_paginated(
+        &self,
+        from_index: usize,
+        limit: usize,
+    ) -> Vec<(AccountId, WhitelistMode)> {
+        self.whitelist_tokens
+            .iter()
+            .skip(from_index)
+            .take(limit)
+            .collect::<Vec<_>>()
+    }
+
+    /// Returns a page of the whitelist account keys, starting at the specified `from_index`
+    /// and containing a maximum of `limit` keys.
+    pub fn get_whitelist_accounts_paginated(&self, from_index: usize, limit: usize) -> Vec<String> {
+        self.whitelist_accounts
+            .iter()
+            .skip(from_index)
+            .take(limit)
+            .collect::<Vec<_>>()
+    }
+
+    /// Checks whether the `account` is whitelisted for the `token`, regardless of the token whitelist mode.
+    ///
+    /// # Returns
+    ///
+    /// The rule that whitelists the account, or `None` if the account isn't whitelisted for the token.
+    pub fn is_account_whitelisted(
+        &self,
+        token: AccountId,
+        account: AccountId,
+    ) -> Option<AccountWhitelistRule> {
+        self.get_account_whitelist_rule(&token, &account)
+    }
+
+    /// Returns a page of the tokens for which the `account` is whitelisted with the combined key {token}:{account},
+    /// starting at the specified `from_index` and containing a maximum of `limit` tokens.
+    pub fn get_whitelisted_tokens_for_account(
+        &self,
+        account: AccountId,
+        from_index: usize,
+        limit: usize,
+    ) -> Vec<AccountId> {
+        self.account_whitelist_tokens
+            .get(&account)
+            .map(|tokens| {
+                tokens
+                    .iter()
+                    .skip(from_index)
+                    .take(limit)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl FastBridge {
+    fn get_account_whitelist_rule(
+        &self,
+        token: &AccountId,
+        account: &AccountId,
+    ) -> Option<AccountWhitelistRule> {
+        if self
+            .whitelist_accounts
+            .contains(&get_token_account_key(Some(token), account))
+        {
+            Some(AccountWhitelistRule::TokenAccount)
+        } else if self
+            .whitelist_accounts
+            .contains(&get_token_account_key(None, account))
+        {
+            Some(AccountWhitelistRule::Account)
+        } else {
+            None
+        }
+    }
+
+    fn set_token_whitelist_mode_internal(&mut self, token: AccountId, mode: WhitelistMode) {
+        let previous_mode = self.whitelist_tokens.insert(&token, &mode);
+
+        FastBridgeEvent::FastBridgeSetTokenWhitelistModeEvent {
+            token,
+            previous_mode,
+            mode,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    fn add_account_whitelist_entry(
+        &mut self,
+        token: Option<AccountId>,
+        account: AccountId,
+    ) -> WhitelistEntryStatus {
+        if let Some(token) = &token {
+            if self.whitelist_tokens.get(token).is_none() {
+                return WhitelistEntryStatus::TokenModeNotSet;
+            }
+        }
+
+        let inserted = self
+            .whitelist_accounts
+            .insert(&get_token_account_key(token.as_ref(), &account));
+
+        if let (true, Some(token)) = (inserted, &token) {
+            let mut account_tokens = self
+                .account_whitelist_tokens
+                .get(&account)
+                .unwrap_or_else(|| new_account_whitelist_tokens(&account));
+            account_tokens.insert(token);
+            self.account_whitelist_tokens
+                .insert(&account, &account_tokens);
+        }
+
+        FastBridgeEvent::FastBridgeAddTokenToAccountWhitelistEvent {
+            token,
+            account,
+            was_whitelisted: !inserted,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+
+        if inserted {
+            WhitelistEntryStatus::Added
+        } else {
+            WhitelistEntryStatus::AlreadyWhitelisted
+        }
+    }
+
+    fn remove_account_whitelist_entry(
+        &mut self,
+        token: Option<AccountId>,
+        account: AccountId,
+    ) -> WhitelistEntryStatus {
+        let removed = self
+            .whitelist_accounts
+            .remove(&get_token_account_key(token.as_ref(), &account));
+
+        if let (true, Some(token)) = (removed, &token) {
+            if let Some(mut account_tokens) = self.account_whitelist_tokens.get(&account) {
+                account_tokens.remove(token);
+                if account_tokens.is_empty() {
+                    self.account_whitelist_tokens.remove(&account);
+                } else {
+                    self.account_whitelist_tokens
+                        .insert(&account, &account_tokens);
+                }
+            }
+        }
+
+        FastBridgeEvent::FastBridgeRemoveTokenFromAccountWhitelistEvent {
+            token,
+            account,
+            was_whitelisted: removed,
+            changed_by: env::predecessor_account_id(),
+        }
+        .emit();
+
+        if removed {
+            WhitelistEntryStatus::Removed
+        } else {
+            WhitelistEntryStatus::NotWhitelisted
+        }
+    }
 }
@@ -0,0 +1,145 @@
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::Deserialize;
+use near_sdk::{
+    env, ext_contract, near_bindgen, promise_result_as_success, require, AccountId, Gas,
+    PanicOnDefault, Promise, PromiseOrValue,
+};
+
+#[ext_contract(ext_token)]
+trait NEP141Token {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+/// The `ft_transfer_call` message that swaps the transferred tokens.
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapRequest {
+    pub token_out: AccountId,
+    pub min_amount_out: U128,
+    /// The id of the receipt that records the output of the swap, unique for the sender
+    pub receipt_id: U128,
+}
+
+/// The DEX that swaps any token at the fixed rate and keeps the swap outputs on the deposits of the senders.
+#[near_bindgen]
+#[derive(BorshSerialize, BorshDeserialize, PanicOnDefault)]
+pub struct Contract {
+    rate_numerator: u128,
+    rate_denominator: u128,
+    /// Mapping `{account}:{token}` to the deposit of the account
+    deposits: LookupMap<String, u128>,
+    /// Mapping `{account}:{receipt_id}` to the output of the swap
+    receipts: LookupMap<String, u128>,
+}
+
+fn deposit_key(account_id: &AccountId, token_id: &AccountId) -> String {
+    format!("{}:{}", account_id, token_id)
+}
+
+fn receipt_key(account_id: &AccountId, receipt_id: U128) -> String {
+    format!("{}:{}", account_id, receipt_id.0)
+}
+
+#[near_bindgen]
+impl Contract {
+    #[init]
+    pub fn new(rate_numerator: U128, rate_denominator: U128) -> Self {
+        Self {
+            rate_numerator: rate_numerator.0,
+            rate_denominator: rate_denominator.0,
+            deposits: LookupMap::new(b"d".to_vec()),
+            receipts: LookupMap::new(b"r".to_vec()),
+        }
+    }
+
+    pub fn set_rate(&mut self, rate_numerator: U128, rate_denominator: U128) {
+        self.rate_numerator = rate_numerator.0;
+        self.rate_denominator = rate_denominator.0;
+    }
+
+    pub fn get_deposit(&self, account_id: AccountId, token_id: AccountId) -> U128 {
+        U128(
+            self.deposits
+                .get(&deposit_key(&account_id, &token_id))
+                .unwrap_or(0),
+        )
+    }
+
+    /// Gets the output of the swap with the `receipt_id` of the `account_id`, zero if there is no such swap.
+    pub fn get_swap_receipt(&self, account_id: AccountId, receipt_id: U128) -> U128 {
+        U128(
+            self.receipts
+                .get(&receipt_key(&account_id, receipt_id))
+                .unwrap_or(0),
+        )
+    }
+
+    /// Transfers the `amount` of the `token_id` from the deposit to the caller and returns the transferred amount.
+    pub fn withdraw(&mut self, token_id: AccountId, amount: U128) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let key = deposit_key(&account_id, &token_id);
+        let deposit = self.deposits.get(&key).unwrap_or(0);
+        require!(
+            amount.0 > 0 && amount.0 <= deposit,
+            "Not enough deposit to withdraw"
+        );
+        self.deposits.insert(&key, &(deposit - amount.0));
+
+        ext_token::ext(token_id.clone())
+            .with_static_gas(Gas::ONE_TERA * 5)
+            .with_attached_deposit(1)
+            .ft_transfer(account_id.clone(), amount, None)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(Gas::ONE_TERA * 5)
+                    .withdraw_callback(account_id, token_id, amount),
+            )
+    }
+
+    #[private]
+    pub fn withdraw_callback(
+        &mut self,
+        account_id: AccountId,
+        token_id: AccountId,
+        amount: U128,
+    ) -> U128 {
+        if promise_result_as_success().is_some() {
+            return amount;
+        }
+
+        let key = deposit_key(&account_id, &token_id);
+        let deposit = self.deposits.get(&key).unwrap_or(0);
+        self.deposits.insert(&key, &(deposit + amount.0));
+        U128(0)
+    }
+}
+
+#[near_bindgen]
+impl FungibleTokenReceiver for Contract {
+    /// Swaps the transferred tokens to the `token_out` of the `SwapRequest`, adds the output to the deposit
+    /// of the sender and records it with the `receipt_id`. All tokens are returned if the output is less
+    /// than the `min_amount_out`.
+    fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let request: SwapRequest =
+            near_sdk::serde_json::from_str(&msg).unwrap_or_else(|_| env::panic_str("Invalid msg"));
+        let amount_out = amount.0 * self.rate_numerator / self.rate_denominator;
+        if amount_out < request.min_amount_out.0 {
+            return PromiseOrValue::Value(amount);
+        }
+
+        let key = deposit_key(&sender_id, &request.token_out);
+        let deposit = self.deposits.get(&key).unwrap_or(0);
+        self.deposits.insert(&key, &(deposit + amount_out));
+        self.receipts
+            .insert(&receipt_key(&sender_id, request.receipt_id), &amount_out);
+        PromiseOrValue::Value(U128(0))
+    }
+}